@@ -1,2 +1,19 @@
 pub mod pks;
+pub mod pks_coupling;
+pub mod pks_dependencies;
+pub mod pks_enforcement;
+pub mod pks_flow;
+pub mod pks_grouping;
+pub mod pks_health;
+pub mod pks_layers;
+pub mod pks_move_constant;
+pub mod pks_operations;
+pub mod pks_package_yml;
+pub mod pks_public_api;
+pub mod pks_references;
+pub mod pks_search;
+pub mod pks_size;
+pub mod pks_todo;
+pub mod pks_tree_config;
 pub mod pks_tree_node;
+pub mod pks_what_if;
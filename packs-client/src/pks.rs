@@ -1,9 +1,27 @@
+use crate::pks_coupling::{CouplingEdges, CouplingMetrics};
+use crate::pks_dependencies::{add_dependency_warnings, find_unused_dependencies, Dependencies};
+use crate::pks_enforcement::EnforcementCoverage;
+use crate::pks_flow::FlowDiagram;
+use crate::pks_grouping::PackGrouper;
+use crate::pks_health::{HealthScores, HealthWeights};
+use crate::pks_layers::{read_configured_layers, ArchitectureLayers};
+use crate::pks_move_constant::MoveConstantPlan;
+use crate::pks_package_yml::{
+    add_dependency_to_yml, remove_dependency_from_yml, PackageYmlChange, PackageYmlEdit,
+};
+use crate::pks_public_api::PublicApi;
+use crate::pks_search::{search_constant_references, ConstantReference};
+use crate::pks_size::PackSizes;
+use crate::pks_todo::{TodoEntry, TodoStatus};
+use crate::pks_tree_config::TreeConfig;
 use crate::pks_tree_node::{
-    pack_name_to_node_names, prepend_dot_to_path, PksTreeBuilder, PksTreeNode,
+    compact_tree_nodes, filter_tree_nodes, pack_name_to_node_names, sort_tree_nodes,
+    PksTreeBuilder, PksTreeNode,
 };
+use crate::pks_what_if::{WhatIf, WhatIfResult};
 use packs::packs::configuration::Configuration;
 use packs::packs::pack::Pack;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::{
     collections::{BTreeSet, HashMap},
@@ -30,6 +48,18 @@ pub struct Pks {
     constant_violations: Option<Arc<Vec<Arc<ConstantViolation>>>>,
     pks_tree_data: Option<Rc<Vec<PksTreeNode>>>,
     path_violations: Option<Arc<HashMap<String, Arc<PathViolations>>>>,
+    enforcement_coverages: HashMap<String, Arc<EnforcementCoverage>>,
+    architecture_layers: Option<Arc<ArchitectureLayers>>,
+    todo_status: Option<Arc<TodoStatus>>,
+    dependencies: Option<Arc<Dependencies>>,
+    pack_files: Option<Arc<HashMap<String, Arc<Vec<PathBuf>>>>>,
+    health_scores: Option<Arc<HealthScores>>,
+    coupling_metrics: HashMap<CouplingEdges, Arc<CouplingMetrics>>,
+    pack_sizes: Option<Arc<PackSizes>>,
+    public_apis: HashMap<String, Arc<PublicApi>>,
+    flow_diagrams: HashMap<String, Arc<FlowDiagram>>,
+    tree_config: TreeConfig,
+    pack_grouper: Option<Arc<PackGrouper>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -96,9 +126,32 @@ impl Pks {
             pks_tree_data: None,
             path_violations: None,
             num_packs: None,
+            enforcement_coverages: HashMap::new(),
+            architecture_layers: None,
+            todo_status: None,
+            dependencies: None,
+            pack_files: None,
+            health_scores: None,
+            coupling_metrics: HashMap::new(),
+            pack_sizes: None,
+            public_apis: HashMap::new(),
+            flow_diagrams: HashMap::new(),
+            tree_config: TreeConfig::default(),
+            pack_grouper: None,
         }
     }
 
+    pub fn absolute_root(&self) -> PathBuf {
+        self.configuration.absolute_root.clone()
+    }
+
+    /// Re-reads the configuration from disk, dropping all cached data
+    pub fn reload(&mut self) {
+        let tree_config = std::mem::take(&mut self.tree_config);
+        *self = Pks::new(Some(self.absolute_root()));
+        self.tree_config = tree_config;
+    }
+
     pub fn get_packs(&mut self) -> Arc<Vec<Arc<Pack>>> {
         if self.packs.is_none() {
             let mut packs = self
@@ -255,9 +308,31 @@ impl Pks {
 
     pub fn get_pks_tree_data(&mut self) -> Rc<Vec<PksTreeNode>> {
         if self.pks_tree_data.is_none() {
-            let pack_names = self.get_pack_names();
-            let pks_tree_data =
-                PksTreeBuilder::new(pack_names, self.get_path_violations()).children;
+            let pack_grouper = self.get_pack_grouper();
+            let pack_node_names = self
+                .get_pack_names()
+                .into_iter()
+                .map(|pack_name| {
+                    let node_names = pack_grouper.node_names(&pack_name);
+                    (pack_name, node_names)
+                })
+                .collect();
+            let pks_tree_data = PksTreeBuilder::with_node_names(
+                pack_node_names,
+                self.get_path_violations(),
+                self.get_todo_status(),
+                self.get_pack_sizes(),
+            )
+            .children;
+            let mut pks_tree_data = if self.tree_config.compact {
+                compact_tree_nodes(pks_tree_data)
+            } else {
+                pks_tree_data
+            };
+            sort_tree_nodes(&mut pks_tree_data, self.tree_config.sort);
+            if self.tree_config.filter {
+                pks_tree_data = filter_tree_nodes(pks_tree_data, self.tree_config.min_violations);
+            }
             self.pks_tree_data = Some(Rc::new(pks_tree_data));
         }
         self.pks_tree_data.as_ref().unwrap().clone()
@@ -273,68 +348,500 @@ impl Pks {
     pub fn get_path_violations_for_path(&mut self, path: &str) -> Option<Arc<PathViolations>> {
         self.get_path_violations().get(path).cloned()
     }
+    /// Violations of every node in the packs tree, contained or not according to the
+    /// current tree grouping. Key is node path.
     pub fn get_path_violations(&mut self) -> Arc<HashMap<String, Arc<PathViolations>>> {
         if self.path_violations.is_none() {
-            let mut map: HashMap<String, PathViolations> = HashMap::new();
-            let all_violations = self.get_pack_dependent_violations();
-            for violation in all_violations.iter() {
-                let defining_pack_name = prepend_dot_to_path(&violation.defining_pack_name);
-                let referencing_pack_name = prepend_dot_to_path(&violation.referencing_pack_name);
-                for (part, _) in pack_name_to_node_names(&defining_pack_name) {
-                    if part_contained_in_other_path(&part, &referencing_pack_name) {
-                        map.entry(part.to_string())
-                            .or_insert_with(|| PathViolations {
-                                path: part.to_string(),
-                                ..Default::default()
-                            })
-                            .contained_in_violations
-                            .push(violation.clone());
-                    } else {
-                        map.entry(part.to_string())
-                            .or_insert_with(|| PathViolations {
-                                path: part.to_string(),
-                                ..Default::default()
-                            })
-                            .uncontained_in_violations
-                            .push(violation.clone());
-                    }
-                }
+            let pack_grouper = self.get_pack_grouper();
+            let path_violations =
+                build_grouped_path_violations(&self.get_pack_dependent_violations(), |pack_name| {
+                    pack_grouper.node_names(pack_name)
+                });
+            self.path_violations = Some(Arc::new(path_violations));
+        }
 
-                for (part, _) in pack_name_to_node_names(&referencing_pack_name) {
-                    if part_contained_in_other_path(&part, &defining_pack_name) {
-                        map.entry(part.to_string())
-                            .or_insert_with(|| PathViolations {
-                                path: part.to_string(),
-                                ..Default::default()
-                            })
-                            .contained_out_violations
-                            .push(violation.clone());
-                    } else {
-                        map.entry(part.to_string())
-                            .or_insert_with(|| PathViolations {
-                                path: part.to_string(),
-                                ..Default::default()
-                            })
-                            .uncontained_out_violations
-                            .push(violation.clone());
-                    }
+        self.path_violations.as_ref().unwrap().clone()
+    }
+
+    pub fn get_tree_config(&self) -> &TreeConfig {
+        &self.tree_config
+    }
+
+    /// Rebuilds the packs tree. A new grouping or new rules also regroup the packs,
+    /// recomputing which violations each node contains, while sorting, filtering and
+    /// compacting only rearrange the nodes. Fails without changing the config when a rule
+    /// pattern is invalid.
+    pub fn set_tree_config(&mut self, tree_config: TreeConfig) -> anyhow::Result<()> {
+        let regroups = tree_config.grouping != self.tree_config.grouping
+            || tree_config.rules != self.tree_config.rules;
+        if regroups {
+            let pack_grouper = PackGrouper::new(&tree_config, &self.get_packs())?;
+            self.pack_grouper = Some(Arc::new(pack_grouper));
+            self.path_violations = None;
+            // which packs a path contains depends on the grouping
+            self.enforcement_coverages.clear();
+            self.health_scores = None;
+            self.flow_diagrams.clear();
+        }
+        self.tree_config = tree_config;
+        self.pks_tree_data = None;
+        Ok(())
+    }
+
+    /// Maps packs to the nodes of the packs tree, see `PackGrouper::contains` for the packs a
+    /// selected path covers
+    pub fn get_pack_grouper(&mut self) -> Arc<PackGrouper> {
+        if self.pack_grouper.is_none() {
+            let packs = self.get_packs();
+            let pack_grouper = PackGrouper::new(&self.tree_config, &packs)
+                .expect("grouping rules are validated when the grouping is set");
+            self.pack_grouper = Some(Arc::new(pack_grouper));
+        }
+        self.pack_grouper.as_ref().unwrap().clone()
+    }
+
+    /// Enforcement settings aggregated across the packs at or beneath `path`.
+    /// Use "." for the whole project.
+    pub fn get_enforcement_coverage(&mut self, path: &str) -> Arc<EnforcementCoverage> {
+        if !self.enforcement_coverages.contains_key(path) {
+            let coverage =
+                EnforcementCoverage::new(path, &self.get_packs(), &self.get_pack_grouper());
+            self.enforcement_coverages
+                .insert(path.to_string(), Arc::new(coverage));
+        }
+        self.enforcement_coverages.get(path).unwrap().clone()
+    }
+
+    pub fn get_architecture_layers(&mut self) -> Arc<ArchitectureLayers> {
+        if self.architecture_layers.is_none() {
+            let configured_layers = read_configured_layers(&self.configuration.absolute_root);
+            let architecture_layers = ArchitectureLayers::new(
+                configured_layers,
+                &self.get_packs(),
+                &self.get_pack_dependent_violations(),
+            );
+            self.architecture_layers = Some(Arc::new(architecture_layers));
+        }
+        self.architecture_layers.as_ref().unwrap().clone()
+    }
+
+    /// The result of the last `check_todos`, if it has been run
+    pub fn get_todo_status(&self) -> Option<Arc<TodoStatus>> {
+        self.todo_status.clone()
+    }
+
+    /// Runs the packs checker and compares the violations it finds with the ones recorded
+    /// in `package_todo.yml` files. This parses every included file, so it is not cached
+    /// implicitly like the other getters. The UI runs `TodoStatus::check_project` in the
+    /// background instead and hands the result to `set_todo_status`.
+    pub fn check_todos(&mut self) -> anyhow::Result<Arc<TodoStatus>> {
+        let todo_status = TodoStatus::check(&self.configuration)?;
+        Ok(self.set_todo_status(todo_status))
+    }
+
+    pub fn set_todo_status(&mut self, todo_status: TodoStatus) -> Arc<TodoStatus> {
+        let todo_status = Arc::new(todo_status);
+        self.todo_status = Some(todo_status.clone());
+        // tree nodes carry the todo counts
+        self.pks_tree_data = None;
+        todo_status
+    }
+
+    /// The dependencies passed to `set_dependencies`, None until they have been checked
+    pub fn get_dependencies(&self) -> Option<Arc<Dependencies>> {
+        self.dependencies.clone()
+    }
+
+    /// Compares each pack's declared dependencies with the packs it references. Finding unused
+    /// dependencies reads the files of every pack with dependencies, so the UI runs the
+    /// returned checker in the background and hands its result to `set_dependencies`.
+    pub fn dependencies_checker(
+        &mut self,
+    ) -> impl FnOnce() -> anyhow::Result<Dependencies> + Send + 'static {
+        let absolute_root = self.absolute_root();
+        let packs = self.get_packs();
+        let pack_files = self.get_pack_files();
+        let violations = self.get_pack_dependent_violations();
+        move || {
+            let unused_dependencies =
+                find_unused_dependencies(&absolute_root, &packs, &pack_files)?;
+            Ok(Dependencies::new(&packs, &unused_dependencies, &violations))
+        }
+    }
+
+    pub fn set_dependencies(&mut self, dependencies: Dependencies) -> Arc<Dependencies> {
+        let dependencies = Arc::new(dependencies);
+        self.dependencies = Some(dependencies.clone());
+        dependencies
+    }
+
+    /// Checks the dependencies right away, see `dependencies_checker`
+    pub fn check_dependencies(&mut self) -> anyhow::Result<Arc<Dependencies>> {
+        let dependencies = self.dependencies_checker()()?;
+        Ok(self.set_dependencies(dependencies))
+    }
+
+    /// Plans removing an unused dependency from the pack's `package.yml`. Nothing is written
+    /// until `apply_package_yml_edit`.
+    pub fn plan_remove_dependency(
+        &mut self,
+        pack_name: &str,
+        dependency_name: &str,
+    ) -> anyhow::Result<PackageYmlEdit> {
+        let packs = self.get_packs();
+        let pack = packs
+            .iter()
+            .find(|pack| pack.name == pack_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown pack {}", pack_name))?;
+        let original_contents = std::fs::read_to_string(&pack.yml)?;
+        let updated_contents = remove_dependency_from_yml(&original_contents, dependency_name)?
+            .ok_or_else(|| {
+                anyhow::anyhow!("{} does not depend on {}", pack_name, dependency_name)
+            })?;
+        Ok(PackageYmlEdit {
+            pack_name: pack_name.to_string(),
+            yml_path: pack.yml.clone(),
+            change: PackageYmlChange::RemoveDependency(dependency_name.to_string()),
+            original_contents,
+            updated_contents,
+            warnings: vec![],
+        })
+    }
+
+    /// Plans declaring `defining_pack_name` as a dependency of `referencing_pack_name`,
+    /// with warnings for cycles and layer violations. Nothing is written until
+    /// `apply_package_yml_edit`.
+    pub fn plan_add_dependency(
+        &mut self,
+        referencing_pack_name: &str,
+        defining_pack_name: &str,
+    ) -> anyhow::Result<PackageYmlEdit> {
+        let packs = self.get_packs();
+        let pack = packs
+            .iter()
+            .find(|pack| pack.name == referencing_pack_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown pack {}", referencing_pack_name))?;
+        let original_contents = std::fs::read_to_string(&pack.yml)?;
+        let updated_contents = add_dependency_to_yml(&original_contents, defining_pack_name)?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{} already depends on {}",
+                    referencing_pack_name,
+                    defining_pack_name
+                )
+            })?;
+        let configured_layers = read_configured_layers(&self.configuration.absolute_root);
+        Ok(PackageYmlEdit {
+            pack_name: referencing_pack_name.to_string(),
+            yml_path: pack.yml.clone(),
+            change: PackageYmlChange::AddDependency(defining_pack_name.to_string()),
+            original_contents,
+            updated_contents,
+            warnings: add_dependency_warnings(
+                &packs,
+                &configured_layers,
+                referencing_pack_name,
+                defining_pack_name,
+            ),
+        })
+    }
+
+    /// Simulates moving a constant out of `defining_pack_name` into each of the other packs
+    pub fn plan_constant_move(
+        &mut self,
+        constant_name: &str,
+        defining_pack_name: &str,
+    ) -> MoveConstantPlan {
+        let entries: Vec<TodoEntry> = self
+            .configuration
+            .pack_set
+            .all_violations
+            .iter()
+            .filter(|violation| violation.constant_name == constant_name)
+            .map(TodoEntry::from)
+            .collect();
+        let configured_layers = read_configured_layers(&self.configuration.absolute_root);
+        MoveConstantPlan::new(
+            constant_name,
+            defining_pack_name,
+            &entries,
+            &self.get_packs(),
+            &configured_layers,
+        )
+    }
+
+    /// Recorded violations of every constant whose name contains `query`
+    pub fn search_constants(&self, query: &str) -> Vec<ConstantReference> {
+        let entries: Vec<TodoEntry> = self
+            .configuration
+            .pack_set
+            .all_violations
+            .iter()
+            .map(TodoEntry::from)
+            .collect();
+        search_constant_references(&entries, query)
+    }
+
+    /// Key is pack name, value is the included files owned by the pack, i.e. not by a pack
+    /// nested inside it, relative to the project root and sorted
+    pub fn get_pack_files(&mut self) -> Arc<HashMap<String, Arc<Vec<PathBuf>>>> {
+        if self.pack_files.is_none() {
+            let packs = self.get_packs();
+            let root_pack = packs.iter().find(|pack| pack.name == ".");
+            let packs_by_path: HashMap<&Path, &Arc<Pack>> = packs
+                .iter()
+                .filter(|pack| pack.name != ".")
+                .map(|pack| (pack.relative_path.as_path(), pack))
+                .collect();
+            let mut pack_files: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for file in self.configuration.included_files.iter() {
+                let relative_file = file
+                    .strip_prefix(&self.configuration.absolute_root)
+                    .unwrap_or(file);
+                // the closest directory with a package.yml
+                let owning_pack = relative_file
+                    .ancestors()
+                    .skip(1)
+                    .find_map(|directory| packs_by_path.get(directory).copied())
+                    .or(root_pack);
+                if let Some(pack) = owning_pack {
+                    pack_files
+                        .entry(pack.name.clone())
+                        .or_default()
+                        .push(relative_file.to_path_buf());
                 }
             }
-            self.path_violations = Some(Arc::new(map.drain().fold(
-                HashMap::new(),
-                |mut collect_map, (name, mut violation)| {
-                    violation.set_violations_count();
-                    collect_map.insert(name, Arc::new(violation));
-                    collect_map
-                },
-            )));
+            self.pack_files = Some(Arc::new(
+                pack_files
+                    .into_iter()
+                    .map(|(pack_name, mut files)| {
+                        files.sort();
+                        (pack_name, Arc::new(files))
+                    })
+                    .collect(),
+            ));
         }
+        self.pack_files.as_ref().unwrap().clone()
+    }
 
-        self.path_violations.as_ref().unwrap().clone()
+    /// Key is pack name, value is the number of included files owned by the pack
+    pub fn get_pack_file_counts(&mut self) -> HashMap<String, usize> {
+        self.get_pack_files()
+            .iter()
+            .map(|(pack_name, files)| (pack_name.clone(), files.len()))
+            .collect()
+    }
+
+    /// The sizes passed to `set_pack_sizes`, None until they have been counted
+    pub fn get_pack_sizes(&self) -> Option<Arc<PackSizes>> {
+        self.pack_sizes.clone()
+    }
+
+    /// Counting lines reads every included file, so the UI runs the returned counter in the
+    /// background and hands its result to `set_pack_sizes`; the tree shows sizes from then on
+    pub fn pack_sizes_counter(&mut self) -> impl FnOnce() -> PackSizes + Send + 'static {
+        let absolute_root = self.absolute_root();
+        let packs = self.get_packs();
+        let pack_files = self.get_pack_files();
+        move || PackSizes::new(&absolute_root, &packs, &pack_files)
+    }
+
+    pub fn set_pack_sizes(&mut self, pack_sizes: PackSizes) -> Arc<PackSizes> {
+        let pack_sizes = Arc::new(pack_sizes);
+        self.pack_sizes = Some(pack_sizes.clone());
+        // tree nodes carry the sizes
+        self.pks_tree_data = None;
+        pack_sizes
+    }
+
+    /// Counts the pack sizes right away, see `pack_sizes_counter`
+    pub fn count_pack_sizes(&mut self) -> Arc<PackSizes> {
+        let pack_sizes = self.pack_sizes_counter()();
+        self.set_pack_sizes(pack_sizes)
     }
+
+    /// The public API passed to `set_public_api`, None until it has been found
+    pub fn get_public_api(&self, pack_name: &str) -> Option<Arc<PublicApi>> {
+        self.public_apis.get(pack_name).cloned()
+    }
+
+    /// Finding references reads every file of the other packs, so the UI runs the returned
+    /// finder in the background and hands its result to `set_public_api`. None if there is no
+    /// pack named `pack_name`.
+    pub fn public_api_finder(
+        &mut self,
+        pack_name: &str,
+    ) -> Option<impl FnOnce() -> PublicApi + Send + 'static> {
+        let packs = self.get_packs();
+        let pack = packs.iter().find(|pack| pack.name == pack_name)?.clone();
+        let absolute_root = self.absolute_root();
+        let pack_files = self.get_pack_files();
+        let constant_violations = self.get_constant_violations();
+        Some(move || PublicApi::new(&pack, &absolute_root, &pack_files, &constant_violations))
+    }
+
+    pub fn set_public_api(&mut self, public_api: PublicApi) -> Arc<PublicApi> {
+        let public_api = Arc::new(public_api);
+        self.public_apis
+            .insert(public_api.pack_name.clone(), public_api.clone());
+        public_api
+    }
+
+    /// Finds the public API right away, see `public_api_finder`
+    pub fn find_public_api(&mut self, pack_name: &str) -> Option<Arc<PublicApi>> {
+        let public_api = self.public_api_finder(pack_name)?();
+        Some(self.set_public_api(public_api))
+    }
+
+    /// Violations between the children of `parent_path`, e.g. "./packs"
+    pub fn get_flow_diagram(&mut self, parent_path: &str) -> Arc<FlowDiagram> {
+        if !self.flow_diagrams.contains_key(parent_path) {
+            let flow_diagram = FlowDiagram::new(
+                parent_path,
+                &self.get_pack_dependent_violations(),
+                &self.get_pack_grouper(),
+            );
+            self.flow_diagrams
+                .insert(parent_path.to_string(), Arc::new(flow_diagram));
+        }
+        self.flow_diagrams.get(parent_path).unwrap().clone()
+    }
+
+    /// Health scores computed with `weights`, recomputed only when the weights change
+    pub fn get_health_scores(&mut self, weights: &HealthWeights) -> Arc<HealthScores> {
+        let is_current = self
+            .health_scores
+            .as_ref()
+            .is_some_and(|health_scores| &health_scores.weights == weights);
+        if !is_current {
+            let health_scores = HealthScores::new(
+                weights.clone(),
+                &self.get_packs(),
+                &self.get_pack_dependent_violations(),
+                &self.get_pack_dependents(),
+                &self.get_pack_file_counts(),
+                &self.get_pack_grouper(),
+            );
+            self.health_scores = Some(Arc::new(health_scores));
+        }
+        self.health_scores.as_ref().unwrap().clone()
+    }
+
+    pub fn get_coupling_metrics(&mut self, edges: CouplingEdges) -> Arc<CouplingMetrics> {
+        if !self.coupling_metrics.contains_key(&edges) {
+            let coupling_metrics = CouplingMetrics::new(
+                edges,
+                &self.get_packs(),
+                &self.get_pack_dependent_violations(),
+                &self.get_pack_files(),
+            );
+            self.coupling_metrics
+                .insert(edges, Arc::new(coupling_metrics));
+        }
+        self.coupling_metrics.get(&edges).unwrap().clone()
+    }
+
+    /// Path violations and tree data as they would be after merging or moving packs.
+    /// Nothing on disk or in the cached data changes.
+    pub fn simulate(&mut self, what_if: &WhatIf) -> WhatIfResult {
+        WhatIfResult::new(
+            what_if,
+            &self.get_pack_names(),
+            &self.get_pack_dependent_violations(),
+        )
+    }
+
+    /// Writes a planned `package.yml` edit and reloads. After removing an unused dependency
+    /// the last dependency check is kept, minus the removed dependency.
+    pub fn apply_package_yml_edit(&mut self, edit: &PackageYmlEdit) -> anyhow::Result<()> {
+        edit.write()?;
+        let dependencies = match &edit.change {
+            PackageYmlChange::RemoveDependency(dependency_name) => {
+                self.dependencies.take().map(|dependencies| {
+                    Arc::new(
+                        dependencies.without_unused_dependency(&edit.pack_name, dependency_name),
+                    )
+                })
+            }
+            PackageYmlChange::AddDependency(_) => None,
+        };
+        self.reload();
+        self.dependencies = dependencies;
+        Ok(())
+    }
+}
+
+/// Groups violations by every path above their defining and referencing packs,
+/// split by whether the other pack is contained in that path. Key is path.
+pub(crate) fn build_path_violations(
+    violations: &[Arc<PackDependentViolation>],
+) -> HashMap<String, Arc<PathViolations>> {
+    build_grouped_path_violations(violations, pack_name_to_node_names)
 }
 
-fn part_contained_in_other_path(part: &str, path: &String) -> bool {
+/// Groups violations by every tree node above their defining and referencing packs,
+/// split by whether the other pack is beneath that node too. `node_names` gives the
+/// tree nodes of a pack name, root first. Key is node path.
+pub(crate) fn build_grouped_path_violations(
+    violations: &[Arc<PackDependentViolation>],
+    node_names: impl Fn(&str) -> Vec<(String, String)>,
+) -> HashMap<String, Arc<PathViolations>> {
+    let mut map: HashMap<String, PathViolations> = HashMap::new();
+    for violation in violations.iter() {
+        let defining_paths: Vec<String> = node_names(&violation.defining_pack_name)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        let referencing_paths: Vec<String> = node_names(&violation.referencing_pack_name)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        for part in defining_paths.iter() {
+            let path_violations = path_violations_entry(&mut map, part);
+            if referencing_paths.contains(part) {
+                path_violations
+                    .contained_in_violations
+                    .push(violation.clone());
+            } else {
+                path_violations
+                    .uncontained_in_violations
+                    .push(violation.clone());
+            }
+        }
+
+        for part in referencing_paths.iter() {
+            let path_violations = path_violations_entry(&mut map, part);
+            if defining_paths.contains(part) {
+                path_violations
+                    .contained_out_violations
+                    .push(violation.clone());
+            } else {
+                path_violations
+                    .uncontained_out_violations
+                    .push(violation.clone());
+            }
+        }
+    }
+    map.drain()
+        .fold(HashMap::new(), |mut collect_map, (name, mut violation)| {
+            violation.set_violations_count();
+            collect_map.insert(name, Arc::new(violation));
+            collect_map
+        })
+}
+
+fn path_violations_entry<'a>(
+    map: &'a mut HashMap<String, PathViolations>,
+    path: &str,
+) -> &'a mut PathViolations {
+    map.entry(path.to_string())
+        .or_insert_with(|| PathViolations {
+            path: path.to_string(),
+            ..Default::default()
+        })
+}
+
+pub(crate) fn part_contained_in_other_path(part: &str, path: &String) -> bool {
     if part == path {
         return true;
     }
@@ -359,26 +866,26 @@ impl PackDependentViolation {
     }
 }
 
+/// Pks for one of the apps in tests/fixtures, e.g. "layered_app"
 #[cfg(test)]
-mod tests {
-    use std::path::PathBuf;
+pub(crate) fn fixture_pks(name: &str) -> Pks {
+    Pks::new(Some(
+        PathBuf::from(format!("../tests/fixtures/{}", name))
+            .canonicalize()
+            .expect("Could not canonicalize path"),
+    ))
+}
 
+#[cfg(test)]
+mod tests {
     use super::*;
 
     fn new_pks() -> Pks {
-        Pks::new(Some(
-            PathBuf::from("../tests/fixtures/simple_app")
-                .canonicalize()
-                .expect("Could not canonicalize path"),
-        ))
+        fixture_pks("simple_app")
     }
 
     fn new_pks_with_violations() -> Pks {
-        Pks::new(Some(
-            PathBuf::from("../tests/fixtures/contains_stale_violations")
-                .canonicalize()
-                .expect("Could not canonicalize path"),
-        ))
+        fixture_pks("contains_stale_violations")
     }
 
     #[test]
@@ -428,6 +935,16 @@ mod tests {
         assert_eq!(bar_violation.num_constants(), 1);
     }
 
+    #[test]
+    fn test_get_pack_file_counts() {
+        let mut pks = new_pks();
+        let pack_file_counts = pks.get_pack_file_counts();
+        assert_eq!(pack_file_counts.get(".").unwrap(), &1);
+        assert_eq!(pack_file_counts.get("packs/bar").unwrap(), &2);
+        assert_eq!(pack_file_counts.get("packs/baz").unwrap(), &1);
+        assert_eq!(pack_file_counts.get("packs/foo").unwrap(), &3);
+    }
+
     #[test]
     fn test_part_contained_in_other_path() {
         assert!(part_contained_in_other_path(
@@ -540,6 +1057,65 @@ mod tests {
         assert!(bar.has_package_definition);
     }
 
+    #[test]
+    fn test_get_pks_tree_data_counts() {
+        let mut pks = new_pks_with_violations();
+        let num_packs = pks.get_num_packs();
+        let pks_tree_data = pks.get_pks_tree_data();
+        let root = &pks_tree_data[0];
+        assert_eq!(root.pack_count, num_packs);
+        let packs = root
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|node| node.path == "./packs")
+            .unwrap();
+        assert_eq!(packs.contained_in_violation_count, 4);
+        assert_eq!(packs.contained_out_violation_count, 4);
+        assert_eq!(packs.in_violation_count, 0);
+    }
+
+    #[test]
+    fn test_plan_remove_dependency() {
+        let mut pks = new_pks();
+        let edit = pks
+            .plan_remove_dependency("packs/foo", "packs/baz")
+            .unwrap();
+        assert_eq!(
+            edit.change,
+            PackageYmlChange::RemoveDependency(String::from("packs/baz"))
+        );
+        assert_eq!(
+            edit.updated_contents,
+            "enforce_dependencies: true\nenforce_privacy: true\ndependencies: []\n"
+        );
+        assert!(pks
+            .plan_remove_dependency("packs/foo", "packs/bar")
+            .is_err());
+    }
+
+    #[test]
+    fn test_set_tree_config_keeps_grouping() {
+        let mut pks = new_pks_with_violations();
+        let path_violations = pks.get_path_violations();
+        let pack_grouper = pks.get_pack_grouper();
+
+        let mut tree_config = pks.get_tree_config().clone();
+        tree_config.sort = tree_config.sort.next();
+        tree_config.filter = true;
+        tree_config.compact = true;
+        pks.set_tree_config(tree_config.clone()).unwrap();
+        assert!(Arc::ptr_eq(&path_violations, &pks.get_path_violations()));
+        assert!(Arc::ptr_eq(&pack_grouper, &pks.get_pack_grouper()));
+        assert_eq!(pks.get_tree_config(), &tree_config);
+
+        tree_config.grouping = tree_config.grouping.next();
+        pks.set_tree_config(tree_config).unwrap();
+        assert!(!Arc::ptr_eq(&path_violations, &pks.get_path_violations()));
+        assert!(!Arc::ptr_eq(&pack_grouper, &pks.get_pack_grouper()));
+    }
+
     // fn new_pks_with_service_violations() -> Pks {
     //     Pks::new(Some(
     //         PathBuf::from("../tests/fixtures/services_contains_stale_violations")
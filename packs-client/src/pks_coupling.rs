@@ -0,0 +1,189 @@
+use crate::pks::PackDependentViolation;
+use packs::packs::pack::Pack;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DEFAULT_PUBLIC_FOLDER: &str = "app/public";
+
+/// Which edges between packs the metrics are computed from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CouplingEdges {
+    /// `dependencies` declared in `package.yml`
+    #[default]
+    Declared,
+    /// Referencing pack to defining pack of every recorded violation
+    Violations,
+}
+
+impl CouplingEdges {
+    pub fn title(&self) -> &'static str {
+        match self {
+            CouplingEdges::Declared => "declared dependencies",
+            CouplingEdges::Violations => "violations",
+        }
+    }
+}
+
+/// Robert C. Martin's package metrics for one pack
+#[derive(Debug, PartialEq, Default)]
+pub struct PackCoupling {
+    pub pack_name: String,
+    /// Ca: packs that depend on this pack
+    pub afferent: usize,
+    /// Ce: packs this pack depends on
+    pub efferent: usize,
+    /// Ce / (Ca + Ce), 0.0 for a pack without edges
+    pub instability: f64,
+    pub public_file_count: usize,
+    pub file_count: usize,
+    /// Share of the pack's files in its public folder, 0.0 for a pack without files
+    pub abstractness: f64,
+    /// |A + I - 1|, how far the pack is from the balance of abstractness and stability
+    pub distance: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CouplingMetrics {
+    pub edges: CouplingEdges,
+    /// Sorted by pack name
+    pub packs: Vec<PackCoupling>,
+}
+
+impl CouplingMetrics {
+    pub fn new(
+        edges: CouplingEdges,
+        packs: &[Arc<Pack>],
+        violations: &[Arc<PackDependentViolation>],
+        pack_files: &HashMap<String, Arc<Vec<PathBuf>>>,
+    ) -> Self {
+        let pack_names: BTreeSet<&str> = packs.iter().map(|pack| pack.name.as_str()).collect();
+        let edge_pairs: Vec<(&str, &str)> = match edges {
+            CouplingEdges::Declared => packs
+                .iter()
+                .flat_map(|pack| {
+                    pack.dependencies
+                        .iter()
+                        .map(|dependency| (pack.name.as_str(), dependency.as_str()))
+                })
+                .collect(),
+            CouplingEdges::Violations => violations
+                .iter()
+                .map(|violation| {
+                    (
+                        violation.referencing_pack_name.as_str(),
+                        violation.defining_pack_name.as_str(),
+                    )
+                })
+                .collect(),
+        };
+        let mut afferent: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        let mut efferent: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for (from, to) in edge_pairs {
+            // edges to packs that do not exist are left to the dependencies screen
+            if from == to || !pack_names.contains(to) {
+                continue;
+            }
+            efferent.entry(from).or_default().insert(to);
+            afferent.entry(to).or_default().insert(from);
+        }
+
+        let mut packs: Vec<PackCoupling> = packs
+            .iter()
+            .map(|pack| {
+                let afferent = afferent.get(pack.name.as_str()).map_or(0, |a| a.len());
+                let efferent = efferent.get(pack.name.as_str()).map_or(0, |e| e.len());
+                let instability = match afferent + efferent {
+                    0 => 0.0,
+                    total => efferent as f64 / total as f64,
+                };
+                let files: &[PathBuf] = pack_files.get(&pack.name).map_or(&[], |files| files);
+                let public_folder = public_folder(pack);
+                let public_file_count = files
+                    .iter()
+                    .filter(|file| file.starts_with(&public_folder))
+                    .count();
+                let abstractness = match files.len() {
+                    0 => 0.0,
+                    file_count => public_file_count as f64 / file_count as f64,
+                };
+                PackCoupling {
+                    pack_name: pack.name.clone(),
+                    afferent,
+                    efferent,
+                    instability,
+                    public_file_count,
+                    file_count: files.len(),
+                    abstractness,
+                    distance: (abstractness + instability - 1.0).abs(),
+                }
+            })
+            .collect();
+        packs.sort_by(|a, b| a.pack_name.cmp(&b.pack_name));
+        Self { edges, packs }
+    }
+}
+
+/// The pack's public folder relative to the project root
+pub(crate) fn public_folder(pack: &Pack) -> PathBuf {
+    let public_folder = pack
+        .public_folder
+        .as_deref()
+        .unwrap_or(Path::new(DEFAULT_PUBLIC_FOLDER));
+    match pack.name.as_str() {
+        "." => public_folder.to_path_buf(),
+        _ => pack.relative_path.join(public_folder),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    fn pack_coupling<'a>(metrics: &'a CouplingMetrics, pack_name: &str) -> &'a PackCoupling {
+        metrics
+            .packs
+            .iter()
+            .find(|pack| pack.pack_name == pack_name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_declared_coupling_metrics() {
+        let mut pks = fixture_pks("layered_app");
+        let metrics = pks.get_coupling_metrics(CouplingEdges::Declared);
+
+        // ui -> payments -> utilities
+        let payments = pack_coupling(&metrics, "packs/payments");
+        assert_eq!((payments.afferent, payments.efferent), (1, 1));
+        assert_eq!(payments.instability, 0.5);
+
+        let utilities = pack_coupling(&metrics, "packs/utilities");
+        assert_eq!((utilities.afferent, utilities.efferent), (1, 0));
+        assert_eq!(utilities.instability, 0.0);
+        assert_eq!(utilities.public_file_count, 1);
+        assert_eq!(utilities.file_count, 2);
+        assert_eq!(utilities.abstractness, 0.5);
+        assert_eq!(utilities.distance, 0.5);
+
+        let ui = pack_coupling(&metrics, "packs/ui");
+        assert_eq!((ui.afferent, ui.efferent), (0, 1));
+        assert_eq!(ui.instability, 1.0);
+        assert_eq!(ui.distance, 0.0);
+    }
+
+    #[test]
+    fn test_violation_coupling_metrics() {
+        let mut pks = fixture_pks("layered_app");
+        let metrics = pks.get_coupling_metrics(CouplingEdges::Violations);
+
+        // ui <-> utilities, orphan -> payments
+        let utilities = pack_coupling(&metrics, "packs/utilities");
+        assert_eq!((utilities.afferent, utilities.efferent), (1, 1));
+        let orphan = pack_coupling(&metrics, "packs/orphan");
+        assert_eq!((orphan.afferent, orphan.efferent), (0, 1));
+        let payments = pack_coupling(&metrics, "packs/payments");
+        assert_eq!((payments.afferent, payments.efferent), (1, 0));
+    }
+}
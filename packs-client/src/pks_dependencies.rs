@@ -0,0 +1,351 @@
+use crate::pks::PackDependentViolation;
+use crate::pks_grouping::PackGrouper;
+use crate::pks_references::{defined_constant_name, RubySource};
+use packs::packs::pack::Pack;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const DEPENDENCY_VIOLATION_TYPE: &str = "dependency";
+
+/// Declared dependencies of a pack compared with the packs it actually references
+#[derive(Debug, PartialEq, Default)]
+pub struct PackDependencies {
+    pub pack_name: String,
+    pub declared_dependencies: Vec<String>,
+    /// Declared in `package.yml` but never referenced; can be removed
+    pub unused_dependencies: Vec<String>,
+    /// Referenced with `dependency` violations but not declared, with the number of violations
+    pub undeclared_dependencies: Vec<(String, usize)>,
+}
+
+impl PackDependencies {
+    pub fn has_issues(&self) -> bool {
+        !self.unused_dependencies.is_empty() || !self.undeclared_dependencies.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Dependencies {
+    /// Key is pack name, every pack is present
+    pub packs: HashMap<String, Arc<PackDependencies>>,
+}
+
+impl Dependencies {
+    pub fn new(
+        packs: &[Arc<Pack>],
+        unused_dependencies: &HashMap<String, Vec<String>>,
+        violations: &[Arc<PackDependentViolation>],
+    ) -> Self {
+        let mut undeclared: HashMap<&str, BTreeMap<String, usize>> = HashMap::new();
+        for violation in violations.iter() {
+            let count = violation
+                .violation_type_counts
+                .get(DEPENDENCY_VIOLATION_TYPE)
+                .copied()
+                .unwrap_or_default();
+            if count > 0 {
+                *undeclared
+                    .entry(violation.referencing_pack_name.as_str())
+                    .or_default()
+                    .entry(violation.defining_pack_name.clone())
+                    .or_default() += count;
+            }
+        }
+
+        let packs = packs
+            .iter()
+            .map(|pack| {
+                let declared_dependencies: BTreeSet<String> =
+                    pack.dependencies.iter().cloned().collect();
+                let mut unused: Vec<String> = unused_dependencies
+                    .get(&pack.name)
+                    .cloned()
+                    .unwrap_or_default();
+                unused.sort();
+                let undeclared_dependencies = undeclared
+                    .remove(pack.name.as_str())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(defining_pack_name, _)| {
+                        !declared_dependencies.contains(defining_pack_name)
+                    })
+                    .collect();
+                (
+                    pack.name.clone(),
+                    Arc::new(PackDependencies {
+                        pack_name: pack.name.clone(),
+                        declared_dependencies: declared_dependencies.into_iter().collect(),
+                        unused_dependencies: unused,
+                        undeclared_dependencies,
+                    }),
+                )
+            })
+            .collect();
+        Self { packs }
+    }
+
+    /// Packs at or beneath `path` with unused or undeclared dependencies, sorted by pack name
+    pub fn pack_dependencies_for_path(
+        &self,
+        path: &str,
+        pack_grouper: &PackGrouper,
+    ) -> Vec<Arc<PackDependencies>> {
+        let mut pack_dependencies: Vec<Arc<PackDependencies>> = self
+            .packs
+            .values()
+            .filter(|pack_dependencies| {
+                pack_dependencies.has_issues()
+                    && pack_grouper.contains(path, &pack_dependencies.pack_name)
+            })
+            .cloned()
+            .collect();
+        pack_dependencies.sort_by(|a, b| a.pack_name.cmp(&b.pack_name));
+        pack_dependencies
+    }
+
+    /// A copy without `dependency_name` in the unused dependencies of `pack_name`
+    pub fn without_unused_dependency(&self, pack_name: &str, dependency_name: &str) -> Self {
+        let mut packs = self.packs.clone();
+        if let Some(pack_dependencies) = packs.get_mut(pack_name) {
+            *pack_dependencies = Arc::new(PackDependencies {
+                pack_name: pack_dependencies.pack_name.clone(),
+                declared_dependencies: pack_dependencies
+                    .declared_dependencies
+                    .iter()
+                    .filter(|name| *name != dependency_name)
+                    .cloned()
+                    .collect(),
+                unused_dependencies: pack_dependencies
+                    .unused_dependencies
+                    .iter()
+                    .filter(|name| *name != dependency_name)
+                    .cloned()
+                    .collect(),
+                undeclared_dependencies: pack_dependencies.undeclared_dependencies.clone(),
+            });
+        }
+        Self { packs }
+    }
+}
+
+/// Problems that declaring `defining_pack_name` as a dependency of `referencing_pack_name`
+/// would introduce: a cycle through the declared dependencies, or a dependency on a higher
+/// architecture layer
+pub fn add_dependency_warnings(
+    packs: &[Arc<Pack>],
+    configured_layers: &[String],
+    referencing_pack_name: &str,
+    defining_pack_name: &str,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Some(cycle) = find_dependency_path(packs, defining_pack_name, referencing_pack_name) {
+        warnings.push(format!(
+            "Introduces a dependency cycle: {} → {}",
+            referencing_pack_name,
+            cycle.join(" → ")
+        ));
+    }
+
+    let layer_index = |pack_name: &str| {
+        let layer = packs
+            .iter()
+            .find(|pack| pack.name == pack_name)?
+            .layer
+            .clone()?;
+        let index = configured_layers.iter().position(|l| *l == layer)?;
+        Some((layer, index))
+    };
+    if let (Some((referencing_layer, referencing_index)), Some((defining_layer, defining_index))) = (
+        layer_index(referencing_pack_name),
+        layer_index(defining_pack_name),
+    ) {
+        if referencing_index > defining_index {
+            warnings.push(format!(
+                "Layer violation: {} ({}) would depend on {} ({}), a higher layer",
+                referencing_pack_name, referencing_layer, defining_pack_name, defining_layer
+            ));
+        }
+    }
+    warnings
+}
+
+/// The shortest chain of declared dependencies from `from` to `to`, both included
+fn find_dependency_path(packs: &[Arc<Pack>], from: &str, to: &str) -> Option<Vec<String>> {
+    let dependencies: HashMap<&str, &std::collections::HashSet<String>> = packs
+        .iter()
+        .map(|pack| (pack.name.as_str(), &pack.dependencies))
+        .collect();
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = std::collections::VecDeque::from([from]);
+    while let Some(pack_name) = queue.pop_front() {
+        if pack_name == to {
+            let mut path = vec![to.to_string()];
+            let mut current = to;
+            while let Some(prev) = previous.get(current) {
+                path.insert(0, prev.to_string());
+                current = prev;
+            }
+            return Some(path);
+        }
+        let mut next: Vec<&String> = dependencies
+            .get(pack_name)
+            .map(|names| names.iter().collect())
+            .unwrap_or_default();
+        next.sort();
+        for dependency_name in next {
+            if dependency_name != from && !previous.contains_key(dependency_name.as_str()) {
+                previous.insert(dependency_name, pack_name);
+                queue.push_back(dependency_name);
+            }
+        }
+    }
+    None
+}
+
+/// Declared dependencies that are never referenced, keyed by pack name. A dependency is used
+/// when a file of the pack mentions a constant the dependency defines, see
+/// `RubySource::references`. This reads every file of the packs with dependencies.
+pub fn find_unused_dependencies(
+    absolute_root: &Path,
+    packs: &[Arc<Pack>],
+    pack_files: &HashMap<String, Arc<Vec<PathBuf>>>,
+) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let files_of = |pack_name: &str| pack_files.get(pack_name).map_or(&[][..], |files| files);
+    let defined_constants: HashMap<&str, Vec<String>> = packs
+        .iter()
+        .map(|pack| {
+            let constants = files_of(&pack.name)
+                .iter()
+                .filter_map(|file| defined_constant_name(pack, file))
+                .collect();
+            (pack.name.as_str(), constants)
+        })
+        .collect();
+
+    let mut unused_dependencies: HashMap<String, Vec<String>> = HashMap::new();
+    for pack in packs.iter().filter(|pack| !pack.dependencies.is_empty()) {
+        let sources = files_of(&pack.name)
+            .iter()
+            .map(|file| RubySource::read(&absolute_root.join(file)))
+            .collect::<anyhow::Result<Vec<RubySource>>>()?;
+        let mut unused: Vec<String> = pack
+            .dependencies
+            .iter()
+            .filter(|dependency_name| {
+                // an unknown pack is reported by `validate`, not here
+                defined_constants
+                    .get(dependency_name.as_str())
+                    .is_some_and(|constants| {
+                        !constants.iter().any(|constant| {
+                            sources.iter().any(|source| source.references(constant))
+                        })
+                    })
+            })
+            .cloned()
+            .collect();
+        if !unused.is_empty() {
+            unused.sort();
+            unused_dependencies.insert(pack.name.clone(), unused);
+        }
+    }
+    Ok(unused_dependencies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_find_unused_dependencies() {
+        let mut pks = fixture_pks("layered_app");
+        let unused_dependencies = find_unused_dependencies(
+            &pks.absolute_root(),
+            &pks.get_packs(),
+            &pks.get_pack_files(),
+        )
+        .unwrap();
+        assert_eq!(
+            unused_dependencies,
+            HashMap::from([
+                (
+                    String::from("packs/payments"),
+                    vec![String::from("packs/utilities")]
+                ),
+                (
+                    String::from("packs/ui"),
+                    vec![String::from("packs/payments")]
+                ),
+            ])
+        );
+
+        let missing_file = HashMap::from([(
+            String::from("packs/ui"),
+            Arc::new(vec![PathBuf::from("packs/ui/app/services/missing.rb")]),
+        )]);
+        let error = find_unused_dependencies(&pks.absolute_root(), &pks.get_packs(), &missing_file)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Could not read"));
+    }
+
+    #[test]
+    fn test_add_dependency_warnings() {
+        let mut pks = fixture_pks("layered_app");
+        let packs = pks.get_packs();
+        let layers = vec![
+            String::from("product"),
+            String::from("domain"),
+            String::from("utilities"),
+        ];
+        assert_eq!(
+            add_dependency_warnings(&packs, &layers, "packs/utilities", "packs/ui"),
+            vec![
+                "Introduces a dependency cycle: packs/utilities → packs/ui → packs/payments → packs/utilities",
+                "Layer violation: packs/utilities (utilities) would depend on packs/ui (product), a higher layer",
+            ]
+        );
+        assert!(
+            add_dependency_warnings(&packs, &layers, "packs/orphan", "packs/payments").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_check_dependencies() {
+        let mut pks = fixture_pks("layered_app");
+        assert!(pks.get_dependencies().is_none());
+        let dependencies = pks.check_dependencies().unwrap();
+
+        let ui = dependencies.packs.get("packs/ui").unwrap();
+        assert_eq!(ui.declared_dependencies, vec!["packs/payments"]);
+        assert_eq!(ui.unused_dependencies, vec!["packs/payments"]);
+        assert!(ui.undeclared_dependencies.is_empty());
+
+        let utilities = dependencies.packs.get("packs/utilities").unwrap();
+        assert!(utilities.unused_dependencies.is_empty());
+        assert_eq!(
+            utilities.undeclared_dependencies,
+            vec![(String::from("packs/ui"), 1)]
+        );
+
+        let pack_names: Vec<String> = dependencies
+            .pack_dependencies_for_path("./packs", &pks.get_pack_grouper())
+            .iter()
+            .map(|pack_dependencies| pack_dependencies.pack_name.clone())
+            .collect();
+        assert_eq!(
+            pack_names,
+            vec![
+                "packs/orphan",
+                "packs/payments",
+                "packs/ui",
+                "packs/utilities"
+            ]
+        );
+
+        let without = dependencies.without_unused_dependency("packs/ui", "packs/payments");
+        let ui = without.packs.get("packs/ui").unwrap();
+        assert!(ui.declared_dependencies.is_empty());
+        assert!(!ui.has_issues());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::pks_grouping::PackGrouper;
+use packs::packs::pack::{CheckerSetting, Pack};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnforcementSetting {
+    Dependencies,
+    Privacy,
+    Architecture,
+    Visibility,
+    FolderPrivacy,
+}
+
+pub const ENFORCEMENT_SETTINGS: [EnforcementSetting; 5] = [
+    EnforcementSetting::Dependencies,
+    EnforcementSetting::Privacy,
+    EnforcementSetting::Architecture,
+    EnforcementSetting::Visibility,
+    EnforcementSetting::FolderPrivacy,
+];
+
+impl EnforcementSetting {
+    /// The key used for this setting in `package.yml`
+    pub fn key(&self) -> &'static str {
+        match self {
+            EnforcementSetting::Dependencies => "enforce_dependencies",
+            EnforcementSetting::Privacy => "enforce_privacy",
+            EnforcementSetting::Architecture => "enforce_architecture",
+            EnforcementSetting::Visibility => "enforce_visibility",
+            EnforcementSetting::FolderPrivacy => "enforce_folder_privacy",
+        }
+    }
+
+    pub fn checker_setting<'a>(&self, pack: &'a Pack) -> Option<&'a CheckerSetting> {
+        match self {
+            EnforcementSetting::Dependencies => pack.enforce_dependencies.as_ref(),
+            EnforcementSetting::Privacy => pack.enforce_privacy.as_ref(),
+            EnforcementSetting::Architecture => pack.enforce_architecture.as_ref(),
+            EnforcementSetting::Visibility => pack.enforce_visibility.as_ref(),
+            EnforcementSetting::FolderPrivacy => pack.enforce_folder_privacy.as_ref(),
+        }
+    }
+
+    pub fn is_enforced(&self, pack: &Pack) -> bool {
+        matches!(
+            self.checker_setting(pack),
+            Some(CheckerSetting::True) | Some(CheckerSetting::Strict)
+        )
+    }
+
+    pub fn is_strict(&self, pack: &Pack) -> bool {
+        matches!(self.checker_setting(pack), Some(CheckerSetting::Strict))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EnforcementCount {
+    pub setting: EnforcementSetting,
+    pub num_packs: usize,
+    pub enforced_count: usize,
+    pub strict_count: usize,
+    pub unenforced_pack_names: Vec<String>,
+}
+
+impl EnforcementCount {
+    /// Ratio of packs enforcing the setting (strictly or not), between 0.0 and 1.0
+    pub fn enforced_ratio(&self) -> f64 {
+        ratio(self.enforced_count, self.num_packs)
+    }
+
+    pub fn strict_ratio(&self) -> f64 {
+        ratio(self.strict_count, self.num_packs)
+    }
+}
+
+/// Enforcement settings aggregated across all packs at or beneath `path`
+#[derive(Debug, PartialEq)]
+pub struct EnforcementCoverage {
+    pub path: String,
+    pub num_packs: usize,
+    pub counts: Vec<EnforcementCount>,
+}
+
+impl EnforcementCoverage {
+    pub fn new(path: &str, packs: &[Arc<Pack>], pack_grouper: &PackGrouper) -> Self {
+        let packs: Vec<&Arc<Pack>> = packs
+            .iter()
+            .filter(|pack| pack_grouper.contains(path, &pack.name))
+            .collect();
+        let counts = ENFORCEMENT_SETTINGS
+            .iter()
+            .map(|setting| {
+                let mut count = EnforcementCount {
+                    setting: *setting,
+                    num_packs: packs.len(),
+                    enforced_count: 0,
+                    strict_count: 0,
+                    unenforced_pack_names: Vec::new(),
+                };
+                for pack in packs.iter() {
+                    if setting.is_enforced(pack) {
+                        count.enforced_count += 1;
+                    } else {
+                        count.unenforced_pack_names.push(pack.name.clone());
+                    }
+                    if setting.is_strict(pack) {
+                        count.strict_count += 1;
+                    }
+                }
+                count
+            })
+            .collect();
+
+        Self {
+            path: path.to_string(),
+            num_packs: packs.len(),
+            counts,
+        }
+    }
+
+    pub fn count_for_setting(&self, setting: EnforcementSetting) -> Option<&EnforcementCount> {
+        self.counts.iter().find(|count| count.setting == setting)
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_enforcement_coverage_for_all_packs() {
+        let mut pks = fixture_pks("simple_app");
+        let coverage = pks.get_enforcement_coverage(".");
+        assert_eq!(coverage.num_packs, 4);
+        assert_eq!(coverage.counts.len(), ENFORCEMENT_SETTINGS.len());
+
+        let privacy = coverage
+            .count_for_setting(EnforcementSetting::Privacy)
+            .unwrap();
+        assert_eq!(privacy.enforced_count, 2);
+        assert_eq!(privacy.strict_count, 0);
+        assert_eq!(privacy.unenforced_pack_names, vec![".", "packs/baz"]);
+        assert_eq!(privacy.enforced_ratio(), 0.5);
+
+        let dependencies = coverage
+            .count_for_setting(EnforcementSetting::Dependencies)
+            .unwrap();
+        assert_eq!(dependencies.enforced_count, 1);
+        assert_eq!(
+            dependencies.unenforced_pack_names,
+            vec![".", "packs/bar", "packs/baz"]
+        );
+
+        let architecture = coverage
+            .count_for_setting(EnforcementSetting::Architecture)
+            .unwrap();
+        assert_eq!(architecture.enforced_count, 0);
+        assert_eq!(architecture.enforced_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_enforcement_coverage_for_subtree() {
+        let mut pks = fixture_pks("simple_app");
+        let coverage = pks.get_enforcement_coverage("./packs/foo");
+        assert_eq!(coverage.path, "./packs/foo");
+        assert_eq!(coverage.num_packs, 1);
+        let privacy = coverage
+            .count_for_setting(EnforcementSetting::Privacy)
+            .unwrap();
+        assert_eq!(privacy.enforced_count, 1);
+        assert!(privacy.unenforced_pack_names.is_empty());
+    }
+
+    #[test]
+    fn test_enforcement_coverage_for_unknown_path() {
+        let mut pks = fixture_pks("simple_app");
+        let coverage = pks.get_enforcement_coverage("./does/not/exist");
+        assert_eq!(coverage.num_packs, 0);
+        let privacy = coverage
+            .count_for_setting(EnforcementSetting::Privacy)
+            .unwrap();
+        assert_eq!(privacy.enforced_ratio(), 0.0);
+    }
+}
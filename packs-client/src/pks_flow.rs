@@ -0,0 +1,128 @@
+use crate::pks::PackDependentViolation;
+use crate::pks_grouping::PackGrouper;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Stands in for every pack that is not beneath the parent path
+pub const OUTSIDE_DOMAIN: &str = "(outside)";
+
+#[derive(Debug, PartialEq)]
+pub struct DomainFlow {
+    /// Domain of the referencing packs
+    pub from: String,
+    /// Domain of the defining packs
+    pub to: String,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct DomainTotals {
+    pub out_count: usize,
+    pub in_count: usize,
+    /// Violations between packs of the same domain, which do not cross a domain boundary
+    pub within_count: usize,
+}
+
+/// Violations aggregated between the children of a tree path, e.g. every `./packs/*`, or the
+/// packs of a group node like "owner:payroll". A violation belongs to a domain when the
+/// domain node contains the pack, see `PackGrouper::child_path`.
+#[derive(Debug, PartialEq)]
+pub struct FlowDiagram {
+    pub parent_path: String,
+    /// Largest first
+    pub flows: Vec<DomainFlow>,
+    /// Key is domain path, `OUTSIDE_DOMAIN` included when violations leave the parent
+    pub domains: BTreeMap<String, DomainTotals>,
+}
+
+impl FlowDiagram {
+    pub fn new(
+        parent_path: &str,
+        violations: &[Arc<PackDependentViolation>],
+        pack_grouper: &PackGrouper,
+    ) -> Self {
+        let mut flow_counts: HashMap<(String, String), usize> = HashMap::new();
+        let mut domains: BTreeMap<String, DomainTotals> = BTreeMap::new();
+        for violation in violations.iter() {
+            let from = pack_grouper.child_path(parent_path, &violation.referencing_pack_name);
+            let to = pack_grouper.child_path(parent_path, &violation.defining_pack_name);
+            if from.is_none() && to.is_none() {
+                continue;
+            }
+            let from = from.unwrap_or_else(|| OUTSIDE_DOMAIN.to_string());
+            let to = to.unwrap_or_else(|| OUTSIDE_DOMAIN.to_string());
+            let count = violation.all_violation_counts();
+            if from == to {
+                domains.entry(from).or_default().within_count += count;
+                continue;
+            }
+            domains.entry(from.clone()).or_default().out_count += count;
+            domains.entry(to.clone()).or_default().in_count += count;
+            *flow_counts.entry((from, to)).or_default() += count;
+        }
+        let mut flows: Vec<DomainFlow> = flow_counts
+            .into_iter()
+            .map(|((from, to), count)| DomainFlow { from, to, count })
+            .collect();
+        flows.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(a.from.cmp(&b.from))
+                .then(a.to.cmp(&b.to))
+        });
+        Self {
+            parent_path: parent_path.to_string(),
+            flows,
+            domains,
+        }
+    }
+
+    pub fn max_flow_count(&self) -> usize {
+        self.flows.iter().map(|flow| flow.count).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+    use crate::pks_tree_config::{TreeConfig, TreeGrouping};
+
+    #[test]
+    fn test_flow_diagram() {
+        let mut pks = fixture_pks("layered_app");
+        let diagram = pks.get_flow_diagram("./packs");
+        // utilities -> ui has two violation types
+        assert_eq!(
+            diagram.flows[0],
+            DomainFlow {
+                from: String::from("./packs/utilities"),
+                to: String::from("./packs/ui"),
+                count: 2,
+            }
+        );
+        assert_eq!(diagram.flows.len(), 3);
+        assert_eq!(diagram.max_flow_count(), 2);
+        let ui = diagram.domains.get("./packs/ui").unwrap();
+        assert_eq!((ui.out_count, ui.in_count), (1, 2));
+
+        // every pack is beneath ./packs, so at the root all violations are within it
+        let root = pks.get_flow_diagram(".");
+        assert!(root.flows.is_empty());
+        assert_eq!(root.domains.get("./packs").unwrap().within_count, 4);
+
+        pks.set_tree_config(TreeConfig {
+            grouping: TreeGrouping::Layer,
+            ..Default::default()
+        })
+        .unwrap();
+        let product = pks.get_flow_diagram("layer:product");
+        assert_eq!(
+            product.domains.keys().collect::<Vec<&String>>(),
+            vec![OUTSIDE_DOMAIN, "./packs/ui"]
+        );
+        let layers = pks.get_flow_diagram(".");
+        assert!(layers.domains.contains_key("layer:product"));
+        assert!(!layers.flows.is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+use crate::pks_tree_config::{TreeConfig, TreeGrouping};
+use crate::pks_tree_node::{pack_name_to_node_names, prepend_dot_to_path};
+use anyhow::Context;
+use glob::Pattern;
+use packs::packs::pack::Pack;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Group of the packs an owner, layer or rule does not cover
+pub const UNGROUPED: &str = "(ungrouped)";
+
+/// Maps each pack to its tree nodes for a `TreeGrouping`
+#[derive(Debug)]
+pub struct PackGrouper {
+    grouping: TreeGrouping,
+    /// Key is pack name, only for the virtual groupings
+    group_names: HashMap<String, String>,
+}
+
+impl PackGrouper {
+    pub fn new(config: &TreeConfig, packs: &[Arc<Pack>]) -> anyhow::Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let pattern = rule.pattern.trim_end_matches('/');
+                let glob = Pattern::new(pattern)
+                    .with_context(|| format!("invalid grouping rule pattern {:?}", rule.pattern))?;
+                Ok((glob, pattern.split('/').count(), rule.name.clone()))
+            })
+            .collect::<anyhow::Result<Vec<(Pattern, usize, Option<String>)>>>()?;
+
+        let group_names = packs
+            .iter()
+            .filter_map(|pack| {
+                let group_name = match config.grouping {
+                    TreeGrouping::Directory | TreeGrouping::Flat => return None,
+                    TreeGrouping::Owner => pack.owner.clone(),
+                    TreeGrouping::Layer => pack.layer.clone(),
+                    TreeGrouping::Rules => rules.iter().find_map(|(glob, depth, name)| {
+                        let segments: Vec<&str> = pack.name.split('/').collect();
+                        if segments.len() < *depth {
+                            return None;
+                        }
+                        let prefix = segments[..*depth].join("/");
+                        glob.matches(&prefix)
+                            .then(|| name.clone().unwrap_or(prefix))
+                    }),
+                };
+                Some((
+                    pack.name.clone(),
+                    group_name.unwrap_or_else(|| UNGROUPED.to_string()),
+                ))
+            })
+            .collect();
+
+        Ok(Self {
+            grouping: config.grouping,
+            group_names,
+        })
+    }
+
+    pub fn grouping(&self) -> TreeGrouping {
+        self.grouping
+    }
+
+    /// The pack's tree nodes as (path, node name), root first. Group nodes have paths like
+    /// "owner:payroll" so they never collide with the directory paths of packs.
+    pub fn node_names(&self, pack_name: &str) -> Vec<(String, String)> {
+        let pack_node = (prepend_dot_to_path(pack_name), pack_name.to_string());
+        match self.grouping {
+            TreeGrouping::Directory => pack_name_to_node_names(pack_name),
+            TreeGrouping::Flat => vec![pack_node],
+            _ => {
+                let group_name = self
+                    .group_names
+                    .get(pack_name)
+                    .map_or(UNGROUPED, |name| name.as_str());
+                vec![
+                    (
+                        format!("{}:{}", self.grouping.title(), group_name),
+                        group_name.to_string(),
+                    ),
+                    pack_node,
+                ]
+            }
+        }
+    }
+
+    /// Whether the pack's tree node is at or beneath the node at `path`. "." is the whole
+    /// project, so it contains every pack whatever the grouping.
+    pub fn contains(&self, path: &str, pack_name: &str) -> bool {
+        path == "."
+            || self
+                .node_names(pack_name)
+                .iter()
+                .any(|(node_path, _)| node_path == path)
+    }
+
+    /// The path of the child of the node at `path` containing the pack, `path` itself for
+    /// the pack at it, or None for a pack outside it
+    pub fn child_path(&self, path: &str, pack_name: &str) -> Option<String> {
+        let node_paths: Vec<String> = self
+            .node_names(pack_name)
+            .into_iter()
+            .map(|(node_path, _)| node_path)
+            .collect();
+        match node_paths.iter().position(|node_path| node_path == path) {
+            Some(position) => node_paths
+                .get(position + 1)
+                .cloned()
+                .or_else(|| Some(path.to_string())),
+            // the virtual groupings have no "." node, their top level nodes are its children
+            None if path == "." => node_paths.into_iter().next(),
+            None => None,
+        }
+    }
+}
+
+/// Whether `path` is a group node of a virtual grouping, e.g. "owner:payroll", rather than a
+/// directory
+pub fn is_group_path(path: &str) -> bool {
+    !path.starts_with('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+    use crate::pks_tree_config::GroupingRule;
+
+    #[test]
+    fn test_node_names() {
+        let mut pks = fixture_pks("layered_app");
+        let packs = pks.get_packs();
+        let grouper = |grouping, rules| {
+            PackGrouper::new(
+                &TreeConfig {
+                    grouping,
+                    rules,
+                    ..Default::default()
+                },
+                &packs,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            grouper(TreeGrouping::Directory, vec![]).node_names("packs/ui"),
+            pack_name_to_node_names("packs/ui")
+        );
+        assert_eq!(
+            grouper(TreeGrouping::Flat, vec![]).node_names("packs/ui"),
+            vec![(String::from("./packs/ui"), String::from("packs/ui"))]
+        );
+        let layer = grouper(TreeGrouping::Layer, vec![]);
+        assert_eq!(
+            layer.node_names("packs/ui"),
+            vec![
+                (String::from("layer:product"), String::from("product")),
+                (String::from("./packs/ui"), String::from("packs/ui")),
+            ]
+        );
+        assert_eq!(layer.node_names("packs/orphan")[0].1, UNGROUPED);
+
+        let rules = grouper(
+            TreeGrouping::Rules,
+            vec![
+                GroupingRule {
+                    pattern: String::from("packs/u*/"),
+                    name: Some(String::from("frontend")),
+                },
+                GroupingRule {
+                    pattern: String::from("packs/*"),
+                    name: None,
+                },
+            ],
+        );
+        assert_eq!(rules.node_names("packs/utilities")[0].1, "frontend");
+        assert_eq!(rules.node_names("packs/payments")[0].1, "packs/payments");
+        assert_eq!(rules.node_names(".")[0].1, UNGROUPED);
+    }
+
+    #[test]
+    fn test_child_path() {
+        let directory = PackGrouper::new(&TreeConfig::default(), &[]).unwrap();
+        assert_eq!(
+            directory.child_path("./packs", "packs/foo/bar"),
+            Some(String::from("./packs/foo"))
+        );
+        assert_eq!(
+            directory.child_path("./packs/foo", "packs/foo"),
+            Some(String::from("./packs/foo"))
+        );
+        assert_eq!(directory.child_path("./packs/foo", "packs/bar"), None);
+        assert_eq!(
+            directory.child_path(".", "packs/foo"),
+            Some(String::from("./packs"))
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut pks = fixture_pks("layered_app");
+        let packs = pks.get_packs();
+        let directory = PackGrouper::new(&TreeConfig::default(), &packs).unwrap();
+        assert!(directory.contains(".", "packs/ui"));
+        assert!(directory.contains("./packs", "packs/ui"));
+        assert!(!directory.contains("./packs/ui", "packs/utilities"));
+        assert_eq!(
+            directory.child_path(".", "packs/ui"),
+            Some(String::from("./packs"))
+        );
+        assert_eq!(
+            directory.child_path("./packs/ui", "packs/ui"),
+            Some(String::from("./packs/ui"))
+        );
+        assert_eq!(directory.child_path("./packs/ui", "packs/payments"), None);
+
+        let layer = PackGrouper::new(
+            &TreeConfig {
+                grouping: TreeGrouping::Layer,
+                ..Default::default()
+            },
+            &packs,
+        )
+        .unwrap();
+        assert!(layer.contains("layer:product", "packs/ui"));
+        assert!(!layer.contains("layer:product", "packs/utilities"));
+        assert!(layer.contains(".", "packs/utilities"));
+        assert!(!layer.contains("./packs", "packs/ui"));
+        assert_eq!(
+            layer.child_path("layer:product", "packs/ui"),
+            Some(String::from("./packs/ui"))
+        );
+        assert_eq!(
+            layer.child_path(".", "packs/ui"),
+            Some(String::from("layer:product"))
+        );
+        assert!(is_group_path("layer:product"));
+        assert!(!is_group_path("./packs/ui"));
+    }
+
+    #[test]
+    fn test_invalid_rule() {
+        let config = TreeConfig {
+            grouping: TreeGrouping::Rules,
+            rules: vec![GroupingRule {
+                pattern: String::from("packs/[*"),
+                name: None,
+            }],
+            ..Default::default()
+        };
+        assert!(PackGrouper::new(&config, &[]).is_err());
+    }
+
+    #[test]
+    fn test_grouped_path_violations() {
+        let mut pks = fixture_pks("layered_app");
+        pks.set_tree_config(TreeConfig {
+            grouping: TreeGrouping::Rules,
+            rules: vec![GroupingRule {
+                pattern: String::from("packs/u*"),
+                name: Some(String::from("frontend")),
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+        // ui and utilities reference each other, so within the group nothing leaves it
+        let frontend = pks.get_path_violations_for_path("rules:frontend").unwrap();
+        assert_eq!(frontend.contained_out_violations_count, 3);
+        assert_eq!(frontend.uncontained_out_violations_count, 0);
+        // a pack only contains its own violations when grouped
+        let ui = pks.get_path_violations_for_path("./packs/ui").unwrap();
+        assert_eq!(ui.uncontained_in_violations_count, 2);
+
+        let tree = pks.get_pks_tree_data();
+        let names: Vec<&str> = tree.iter().map(|node| node.node_name.as_str()).collect();
+        assert_eq!(names, vec![UNGROUPED, "frontend"]);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::pks::{part_contained_in_other_path, PackDependentViolation};
+use crate::pks_dependencies::DEPENDENCY_VIOLATION_TYPE;
+use crate::pks_enforcement::ENFORCEMENT_SETTINGS;
+use crate::pks_grouping::PackGrouper;
+use crate::pks_layers::ARCHITECTURE_VIOLATION_TYPE;
+use crate::pks_move_constant::{
+    FOLDER_VISIBILITY_VIOLATION_TYPE, PRIVACY_VIOLATION_TYPE, VISIBILITY_VIOLATION_TYPE,
+};
+use crate::pks_tree_node::prepend_dot_to_path;
+use packs::packs::pack::Pack;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+pub const MAX_HEALTH_SCORE: f64 = 100.0;
+
+/// The health score formula. A pack starts at `base` and each term below is added to or
+/// subtracted from it; the result is clamped between 0 and 100.
+/// Read from the `health` section of the config file, missing fields use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthWeights {
+    pub base: f64,
+    /// Subtracted per outgoing violation, times the weight of its violation type
+    pub out_violation: f64,
+    /// Subtracted per incoming violation, times the weight of its violation type
+    pub in_violation: f64,
+    /// Multiplier per violation type, 1.0 for types not listed
+    pub violation_types: BTreeMap<String, f64>,
+    /// Subtracted per pack that declares this pack as a dependency
+    pub dependent: f64,
+    /// Added per enforced checker setting in `package.yml`
+    pub enforced_setting: f64,
+    /// Added on top of `enforced_setting` per strict checker setting
+    pub strict_setting: f64,
+    /// Subtracted per included file in the pack
+    pub file: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            base: 80.0,
+            out_violation: 1.0,
+            in_violation: 0.5,
+            violation_types: BTreeMap::from([
+                (String::from(ARCHITECTURE_VIOLATION_TYPE), 1.5),
+                (String::from(DEPENDENCY_VIOLATION_TYPE), 1.0),
+                (String::from(FOLDER_VISIBILITY_VIOLATION_TYPE), 0.5),
+                (String::from(PRIVACY_VIOLATION_TYPE), 1.0),
+                (String::from(VISIBILITY_VIOLATION_TYPE), 1.0),
+            ]),
+            dependent: 0.2,
+            enforced_setting: 2.0,
+            strict_setting: 2.0,
+            file: 0.01,
+        }
+    }
+}
+
+impl HealthWeights {
+    fn violation_penalty(&self, violation: &PackDependentViolation, weight: f64) -> f64 {
+        violation
+            .violation_type_counts
+            .iter()
+            .map(|(violation_type, count)| {
+                let type_weight = self
+                    .violation_types
+                    .get(violation_type)
+                    .copied()
+                    .unwrap_or(1.0);
+                *count as f64 * type_weight * weight
+            })
+            .sum()
+    }
+}
+
+/// A pack's score and the terms it is made of
+#[derive(Debug, PartialEq, Default)]
+pub struct PackHealth {
+    pub pack_name: String,
+    pub score: f64,
+    pub out_violation_penalty: f64,
+    pub in_violation_penalty: f64,
+    pub dependents_count: usize,
+    pub dependents_penalty: f64,
+    pub enforcement_bonus: f64,
+    pub file_count: usize,
+    pub size_penalty: f64,
+}
+
+/// The mean score of the packs at or beneath a tree path
+#[derive(Debug, PartialEq)]
+pub struct PathHealth {
+    pub path: String,
+    pub score: f64,
+    pub num_packs: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HealthScores {
+    pub weights: HealthWeights,
+    /// Best to worst
+    pub packs: Vec<Arc<PackHealth>>,
+    /// Directory paths and packs with nested packs, best to worst
+    pub paths: Vec<PathHealth>,
+}
+
+impl HealthScores {
+    pub fn new(
+        weights: HealthWeights,
+        packs: &[Arc<Pack>],
+        violations: &[Arc<PackDependentViolation>],
+        pack_dependents: &HashMap<String, Arc<std::collections::BTreeSet<String>>>,
+        file_counts: &HashMap<String, usize>,
+        pack_grouper: &PackGrouper,
+    ) -> Self {
+        let mut pack_healths: Vec<Arc<PackHealth>> = packs
+            .iter()
+            .map(|pack| {
+                let out_violation_penalty: f64 = violations
+                    .iter()
+                    .filter(|violation| violation.referencing_pack_name == pack.name)
+                    .map(|violation| weights.violation_penalty(violation, weights.out_violation))
+                    .sum();
+                let in_violation_penalty: f64 = violations
+                    .iter()
+                    .filter(|violation| violation.defining_pack_name == pack.name)
+                    .map(|violation| weights.violation_penalty(violation, weights.in_violation))
+                    .sum();
+                let dependents_count = pack_dependents
+                    .get(&pack.name)
+                    .map_or(0, |dependents| dependents.len());
+                let dependents_penalty = dependents_count as f64 * weights.dependent;
+                let enforcement_bonus: f64 = ENFORCEMENT_SETTINGS
+                    .iter()
+                    .map(|setting| {
+                        let mut bonus = 0.0;
+                        if setting.is_enforced(pack) {
+                            bonus += weights.enforced_setting;
+                        }
+                        if setting.is_strict(pack) {
+                            bonus += weights.strict_setting;
+                        }
+                        bonus
+                    })
+                    .sum();
+                let file_count = file_counts.get(&pack.name).copied().unwrap_or_default();
+                let size_penalty = file_count as f64 * weights.file;
+                let score = (weights.base
+                    - out_violation_penalty
+                    - in_violation_penalty
+                    - dependents_penalty
+                    + enforcement_bonus
+                    - size_penalty)
+                    .clamp(0.0, MAX_HEALTH_SCORE);
+                Arc::new(PackHealth {
+                    pack_name: pack.name.clone(),
+                    score,
+                    out_violation_penalty,
+                    in_violation_penalty,
+                    dependents_count,
+                    dependents_penalty,
+                    enforcement_bonus,
+                    file_count,
+                    size_penalty,
+                })
+            })
+            .collect();
+        pack_healths.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.pack_name.cmp(&b.pack_name))
+        });
+
+        let mut path_scores: HashMap<String, Vec<f64>> = HashMap::new();
+        for pack_health in pack_healths.iter() {
+            for (path, _) in pack_grouper.node_names(&pack_health.pack_name) {
+                path_scores.entry(path).or_default().push(pack_health.score);
+            }
+        }
+        let pack_paths: HashSet<String> = packs
+            .iter()
+            .map(|pack| prepend_dot_to_path(&pack.name))
+            .collect();
+        let mut paths: Vec<PathHealth> = path_scores
+            .into_iter()
+            // a pack without nested packs is already on the pack leaderboard
+            .filter(|(path, scores)| !pack_paths.contains(path) || scores.len() > 1)
+            .map(|(path, scores)| PathHealth {
+                score: scores.iter().sum::<f64>() / scores.len() as f64,
+                num_packs: scores.len(),
+                path,
+            })
+            .collect();
+        paths.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+
+        Self {
+            weights,
+            packs: pack_healths,
+            paths,
+        }
+    }
+
+    /// Packs at or beneath `path`, best to worst
+    pub fn pack_healths_for_path(
+        &self,
+        path: &str,
+        pack_grouper: &PackGrouper,
+    ) -> Vec<Arc<PackHealth>> {
+        self.packs
+            .iter()
+            .filter(|pack_health| pack_grouper.contains(path, &pack_health.pack_name))
+            .cloned()
+            .collect()
+    }
+
+    /// Subtrees at or beneath `path`, best to worst
+    pub fn path_healths_for_path(&self, path: &str) -> Vec<&PathHealth> {
+        self.paths
+            .iter()
+            .filter(|path_health| {
+                path == "." || part_contained_in_other_path(path, &path_health.path)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+    use crate::pks_tree_config::{TreeConfig, TreeGrouping};
+
+    #[test]
+    fn test_health_scores() {
+        let mut pks = fixture_pks("layered_app");
+        let weights = HealthWeights {
+            file: 0.0,
+            ..Default::default()
+        };
+        let scores = pks.get_health_scores(&weights);
+
+        // utilities -> ui: 1 architecture (1.5) and 1 dependency (1.0)
+        let utilities = scores
+            .packs
+            .iter()
+            .find(|pack_health| pack_health.pack_name == "packs/utilities")
+            .unwrap();
+        assert_eq!(utilities.out_violation_penalty, 2.5);
+        // ui -> utilities: 1 privacy at half weight
+        assert_eq!(utilities.in_violation_penalty, 0.5);
+        assert_eq!(utilities.dependents_count, 1);
+        assert_eq!(utilities.enforcement_bonus, 2.0);
+        assert_eq!(utilities.score, 80.0 - 2.5 - 0.5 - 0.2 + 2.0);
+
+        let scores_sorted = scores
+            .packs
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score);
+        assert!(scores_sorted);
+
+        let packs_path = scores
+            .path_healths_for_path(".")
+            .into_iter()
+            .find(|path_health| path_health.path == "./packs")
+            .unwrap();
+        assert_eq!(packs_path.num_packs, 4);
+
+        let directory = pks.get_pack_grouper();
+        assert_eq!(
+            scores.pack_healths_for_path("./packs/ui", &directory).len(),
+            1
+        );
+
+        pks.set_tree_config(TreeConfig {
+            grouping: TreeGrouping::Layer,
+            ..Default::default()
+        })
+        .unwrap();
+        let scores = pks.get_health_scores(&weights);
+        let layer = pks.get_pack_grouper();
+        let product = scores.pack_healths_for_path("layer:product", &layer);
+        assert_eq!(product.len(), 1);
+        assert_eq!(product[0].pack_name, "packs/ui");
+        let product_path = scores.path_healths_for_path("layer:product");
+        assert_eq!(product_path.len(), 1);
+        assert_eq!(product_path[0].num_packs, 1);
+    }
+
+    #[test]
+    fn test_health_weights_defaults_for_missing_fields() {
+        let weights: HealthWeights = serde_yaml::from_str("base: 50.0").unwrap();
+        assert_eq!(weights.base, 50.0);
+        assert_eq!(
+            weights.out_violation,
+            HealthWeights::default().out_violation
+        );
+    }
+
+    #[test]
+    fn test_violation_type_weights() {
+        let violation = PackDependentViolation {
+            defining_pack_name: String::from("packs/a"),
+            referencing_pack_name: String::from("packs/b"),
+            violation_type_counts: HashMap::from([
+                (String::from(FOLDER_VISIBILITY_VIOLATION_TYPE), 2),
+                (String::from(ARCHITECTURE_VIOLATION_TYPE), 1),
+                (String::from("unknown"), 1),
+            ]),
+            constant_counts: HashMap::new(),
+        };
+        // 2 folder visibility at 0.5, 1 architecture at 1.5, and 1 of an unlisted type at 1.0
+        assert_eq!(
+            HealthWeights::default().violation_penalty(&violation, 2.0),
+            (2.0 * 0.5 + 1.5 + 1.0) * 2.0
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::pks::PackDependentViolation;
+use packs::packs::pack::Pack;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const ARCHITECTURE_VIOLATION_TYPE: &str = "architecture";
+
+/// The subset of `packwerk.yml` needed to know the configured layers.
+/// `architecture_layers` is the key packwerk uses, `layers` is the one packs prefers.
+#[derive(Debug, Default, Deserialize)]
+struct PackwerkLayersConfig {
+    #[serde(default)]
+    layers: Vec<String>,
+    #[serde(default)]
+    architecture_layers: Vec<String>,
+}
+
+/// Reads the configured layers, top to bottom, from `packwerk.yml` in `absolute_root`.
+/// A missing or unparseable file is treated as having no layers.
+pub fn read_configured_layers(absolute_root: &Path) -> Vec<String> {
+    let contents = match std::fs::read_to_string(absolute_root.join("packwerk.yml")) {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+    let config: PackwerkLayersConfig = serde_yaml::from_str(&contents).unwrap_or_default();
+    if config.layers.is_empty() {
+        config.architecture_layers
+    } else {
+        config.layers
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Layer {
+    pub name: String,
+    pub pack_names: Vec<String>,
+}
+
+/// Violations from packs in one layer to packs in another
+#[derive(Debug, PartialEq)]
+pub struct LayerViolation {
+    pub referencing_layer: String,
+    pub defining_layer: String,
+    pub count: usize,
+    pub architecture_count: usize,
+    /// true when a lower layer references a higher one
+    pub upward: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ArchitectureLayers {
+    /// Configured layers, top to bottom, with the packs assigned to each
+    pub layers: Vec<Layer>,
+    pub unlayered_pack_names: Vec<String>,
+    pub layer_violations: Vec<LayerViolation>,
+}
+
+impl ArchitectureLayers {
+    pub fn new(
+        configured_layers: Vec<String>,
+        packs: &[Arc<Pack>],
+        violations: &[Arc<PackDependentViolation>],
+    ) -> Self {
+        let mut layers: Vec<Layer> = configured_layers
+            .into_iter()
+            .map(|name| Layer {
+                name,
+                pack_names: Vec::new(),
+            })
+            .collect();
+        let mut unlayered_pack_names = Vec::new();
+        let mut pack_layer_indexes: HashMap<&str, usize> = HashMap::new();
+        for pack in packs.iter() {
+            let layer_index = pack
+                .layer
+                .as_ref()
+                .and_then(|layer| layers.iter().position(|l| &l.name == layer));
+            match layer_index {
+                Some(index) => {
+                    layers[index].pack_names.push(pack.name.clone());
+                    pack_layer_indexes.insert(&pack.name, index);
+                }
+                None => unlayered_pack_names.push(pack.name.clone()),
+            }
+        }
+
+        let mut counts: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for violation in violations.iter() {
+            let referencing = pack_layer_indexes.get(violation.referencing_pack_name.as_str());
+            let defining = pack_layer_indexes.get(violation.defining_pack_name.as_str());
+            if let (Some(referencing), Some(defining)) = (referencing, defining) {
+                if referencing == defining {
+                    continue;
+                }
+                let entry = counts.entry((*referencing, *defining)).or_default();
+                entry.0 += violation.all_violation_counts();
+                entry.1 += violation.count_for_violation_type(ARCHITECTURE_VIOLATION_TYPE);
+            }
+        }
+        let mut layer_violations: Vec<LayerViolation> = counts
+            .into_iter()
+            .map(
+                |((referencing, defining), (count, architecture_count))| LayerViolation {
+                    referencing_layer: layers[referencing].name.clone(),
+                    defining_layer: layers[defining].name.clone(),
+                    count,
+                    architecture_count,
+                    upward: referencing > defining,
+                },
+            )
+            .collect();
+        layer_violations.sort_by(|a, b| {
+            b.upward
+                .cmp(&a.upward)
+                .then(b.count.cmp(&a.count))
+                .then(a.referencing_layer.cmp(&b.referencing_layer))
+                .then(a.defining_layer.cmp(&b.defining_layer))
+        });
+
+        Self {
+            layers,
+            unlayered_pack_names,
+            layer_violations,
+        }
+    }
+
+    pub fn layer_violations_from(&self, referencing_layer: &str) -> Vec<&LayerViolation> {
+        self.layer_violations
+            .iter()
+            .filter(|violation| violation.referencing_layer == referencing_layer)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_read_configured_layers() {
+        let layers = read_configured_layers(
+            &PathBuf::from("../tests/fixtures/layered_app")
+                .canonicalize()
+                .unwrap(),
+        );
+        assert_eq!(layers, vec!["product", "domain", "utilities"]);
+
+        let layers = read_configured_layers(
+            &PathBuf::from("../tests/fixtures/simple_app")
+                .canonicalize()
+                .unwrap(),
+        );
+        assert!(layers.is_empty());
+    }
+
+    #[test]
+    fn test_get_architecture_layers() {
+        let mut pks = fixture_pks("layered_app");
+        let layers = pks.get_architecture_layers();
+        let layer_names: Vec<&str> = layers.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(layer_names, vec!["product", "domain", "utilities"]);
+        assert_eq!(layers.layers[0].pack_names, vec!["packs/ui"]);
+        assert_eq!(layers.layers[1].pack_names, vec!["packs/payments"]);
+        assert_eq!(layers.layers[2].pack_names, vec!["packs/utilities"]);
+        assert_eq!(layers.unlayered_pack_names, vec![".", "packs/orphan"]);
+
+        // orphan -> payments is ignored because orphan has no layer
+        assert_eq!(layers.layer_violations.len(), 2);
+        let upward = &layers.layer_violations[0];
+        assert!(upward.upward);
+        assert_eq!(upward.referencing_layer, "utilities");
+        assert_eq!(upward.defining_layer, "product");
+        assert_eq!(upward.count, 2);
+        assert_eq!(upward.architecture_count, 1);
+
+        let downward = &layers.layer_violations[1];
+        assert!(!downward.upward);
+        assert_eq!(downward.referencing_layer, "product");
+        assert_eq!(downward.defining_layer, "utilities");
+        assert_eq!(downward.count, 1);
+        assert_eq!(downward.architecture_count, 0);
+
+        assert_eq!(layers.layer_violations_from("utilities").len(), 1);
+        assert!(layers.layer_violations_from("domain").is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::pks_dependencies::DEPENDENCY_VIOLATION_TYPE;
+use crate::pks_enforcement::EnforcementSetting;
+use crate::pks_layers::ARCHITECTURE_VIOLATION_TYPE;
+use crate::pks_todo::TodoEntry;
+use packs::packs::pack::Pack;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+pub const PRIVACY_VIOLATION_TYPE: &str = "privacy";
+pub const VISIBILITY_VIOLATION_TYPE: &str = "visibility";
+pub const FOLDER_VISIBILITY_VIOLATION_TYPE: &str = "folder_visibility";
+
+/// How the violation counts of one pack would change if the constant moved
+#[derive(Debug, PartialEq)]
+pub struct PackViolationChange {
+    pub pack_name: String,
+    pub out_before: usize,
+    pub out_after: usize,
+    pub in_before: usize,
+    pub in_after: usize,
+}
+
+impl PackViolationChange {
+    pub fn delta(&self) -> isize {
+        (self.out_after + self.in_after) as isize - (self.out_before + self.in_before) as isize
+    }
+}
+
+/// The violations the constant would have if it were defined in `pack_name`
+#[derive(Debug, PartialEq)]
+pub struct MoveCandidate {
+    pub pack_name: String,
+    pub total: usize,
+    /// Known references from inside the candidate, which would no longer cross packs
+    pub local_reference_count: usize,
+    pub violation_type_counts: BTreeMap<String, usize>,
+    /// Only packs whose counts change, sorted by pack name
+    pub pack_changes: Vec<PackViolationChange>,
+}
+
+impl MoveCandidate {
+    pub fn count_for_violation_type(&self, violation_type: &str) -> usize {
+        self.violation_type_counts
+            .get(violation_type)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Simulates relocating a constant to every other pack under the current dependency
+/// declarations and enforcement settings.
+/// Only references recorded in `package_todo.yml` are known, so references that are not
+/// violations today (e.g. from packs that declare the dependency) are not simulated.
+#[derive(Debug, PartialEq)]
+pub struct MoveConstantPlan {
+    pub constant_name: String,
+    pub defining_pack_name: String,
+    /// Recorded violations of the constant where it is defined today
+    pub current_total: usize,
+    /// Whether the constant is outside the public folder, inferred from privacy violations
+    pub private: bool,
+    /// Best destinations first: fewest violations, then most local references, then pack name
+    pub candidates: Vec<MoveCandidate>,
+}
+
+impl MoveConstantPlan {
+    pub fn new(
+        constant_name: &str,
+        defining_pack_name: &str,
+        entries: &[TodoEntry],
+        packs: &[Arc<Pack>],
+        configured_layers: &[String],
+    ) -> Self {
+        let entries: Vec<&TodoEntry> = entries
+            .iter()
+            .filter(|entry| {
+                entry.constant_name == constant_name
+                    && entry.defining_pack_name == defining_pack_name
+            })
+            .collect();
+        let private = entries
+            .iter()
+            .any(|entry| entry.violation_type == PRIVACY_VIOLATION_TYPE);
+        // one reference per referencing file, however many violation types it has
+        let references: BTreeSet<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.referencing_pack_name.as_str(), entry.file.as_str()))
+            .collect();
+        let mut out_before: HashMap<&str, usize> = HashMap::new();
+        for entry in entries.iter() {
+            *out_before
+                .entry(entry.referencing_pack_name.as_str())
+                .or_default() += 1;
+        }
+
+        let packs_by_name: HashMap<&str, &Pack> = packs
+            .iter()
+            .map(|pack| (pack.name.as_str(), pack.as_ref()))
+            .collect();
+        let layer_index = |pack: &Pack| {
+            pack.layer
+                .as_ref()
+                .and_then(|layer| configured_layers.iter().position(|l| l == layer))
+        };
+
+        let mut candidates: Vec<MoveCandidate> = packs
+            .iter()
+            .filter(|pack| pack.name != defining_pack_name)
+            .map(|candidate| {
+                let mut violation_type_counts: BTreeMap<String, usize> = BTreeMap::new();
+                let mut out_after: HashMap<&str, usize> = HashMap::new();
+                let mut local_reference_count = 0;
+                for (referencing_pack_name, _) in references.iter() {
+                    if *referencing_pack_name == candidate.name {
+                        local_reference_count += 1;
+                        continue;
+                    }
+                    let referencing_pack = match packs_by_name.get(referencing_pack_name) {
+                        Some(pack) => pack,
+                        None => continue,
+                    };
+                    let mut violation_types = vec![];
+                    if EnforcementSetting::Dependencies.is_enforced(referencing_pack)
+                        && !referencing_pack.dependencies.contains(&candidate.name)
+                    {
+                        violation_types.push(DEPENDENCY_VIOLATION_TYPE);
+                    }
+                    if private && EnforcementSetting::Privacy.is_enforced(candidate) {
+                        violation_types.push(PRIVACY_VIOLATION_TYPE);
+                    }
+                    if EnforcementSetting::Visibility.is_enforced(candidate)
+                        && !candidate.visible_to.is_empty()
+                        && !candidate.visible_to.contains(*referencing_pack_name)
+                    {
+                        violation_types.push(VISIBILITY_VIOLATION_TYPE);
+                    }
+                    if EnforcementSetting::Architecture.is_enforced(referencing_pack) {
+                        if let (Some(referencing_index), Some(defining_index)) =
+                            (layer_index(referencing_pack), layer_index(candidate))
+                        {
+                            if referencing_index > defining_index {
+                                violation_types.push(ARCHITECTURE_VIOLATION_TYPE);
+                            }
+                        }
+                    }
+                    for violation_type in violation_types {
+                        *violation_type_counts
+                            .entry(violation_type.to_string())
+                            .or_default() += 1;
+                        *out_after.entry(referencing_pack_name).or_default() += 1;
+                    }
+                }
+                let total = violation_type_counts.values().sum();
+                let pack_changes = pack_changes(
+                    defining_pack_name,
+                    &candidate.name,
+                    &out_before,
+                    &out_after,
+                    total,
+                );
+                MoveCandidate {
+                    pack_name: candidate.name.clone(),
+                    total,
+                    local_reference_count,
+                    violation_type_counts,
+                    pack_changes,
+                }
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.total
+                .cmp(&b.total)
+                .then(b.local_reference_count.cmp(&a.local_reference_count))
+                .then(a.pack_name.cmp(&b.pack_name))
+        });
+
+        Self {
+            constant_name: constant_name.to_string(),
+            defining_pack_name: defining_pack_name.to_string(),
+            current_total: entries.len(),
+            private,
+            candidates,
+        }
+    }
+}
+
+fn pack_changes(
+    defining_pack_name: &str,
+    candidate_pack_name: &str,
+    out_before: &HashMap<&str, usize>,
+    out_after: &HashMap<&str, usize>,
+    total_after: usize,
+) -> Vec<PackViolationChange> {
+    let total_before: usize = out_before.values().sum();
+    let mut pack_names: BTreeSet<&str> =
+        out_before.keys().chain(out_after.keys()).copied().collect();
+    pack_names.insert(defining_pack_name);
+    pack_names.insert(candidate_pack_name);
+    pack_names
+        .into_iter()
+        .map(|pack_name| PackViolationChange {
+            pack_name: pack_name.to_string(),
+            out_before: out_before.get(pack_name).copied().unwrap_or_default(),
+            out_after: out_after.get(pack_name).copied().unwrap_or_default(),
+            in_before: if pack_name == defining_pack_name {
+                total_before
+            } else {
+                0
+            },
+            in_after: if pack_name == candidate_pack_name {
+                total_after
+            } else {
+                0
+            },
+        })
+        .filter(|change| {
+            change.out_before != change.out_after || change.in_before != change.in_after
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_move_constant_plan() {
+        let mut pks = fixture_pks("layered_app");
+        let plan = pks.plan_constant_move("::Ui::Formatter", "packs/ui");
+        assert_eq!(plan.current_total, 2);
+        assert!(!plan.private);
+
+        // moving it into the only pack that references it removes every violation
+        let best = plan.candidates.first().unwrap();
+        assert_eq!(best.pack_name, "packs/utilities");
+        assert_eq!(best.total, 0);
+        assert_eq!(
+            best.pack_changes,
+            vec![
+                PackViolationChange {
+                    pack_name: String::from("packs/ui"),
+                    out_before: 0,
+                    out_after: 0,
+                    in_before: 2,
+                    in_after: 0,
+                },
+                PackViolationChange {
+                    pack_name: String::from("packs/utilities"),
+                    out_before: 2,
+                    out_after: 0,
+                    in_before: 0,
+                    in_after: 0,
+                },
+            ]
+        );
+        assert_eq!(best.pack_changes[1].delta(), -2);
+
+        // payments is a higher layer than utilities and is not a declared dependency
+        let payments = plan
+            .candidates
+            .iter()
+            .find(|candidate| candidate.pack_name == "packs/payments")
+            .unwrap();
+        assert_eq!(
+            payments.count_for_violation_type(ARCHITECTURE_VIOLATION_TYPE),
+            1
+        );
+        assert_eq!(
+            payments.count_for_violation_type(DEPENDENCY_VIOLATION_TYPE),
+            0
+        );
+    }
+}
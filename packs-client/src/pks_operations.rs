@@ -0,0 +1,309 @@
+use crate::pks::part_contained_in_other_path;
+use crate::pks_tree_node::prepend_dot_to_path;
+use packs::packs::configuration::Configuration;
+use std::path::Path;
+
+/// Operations from the packs crate that can be run in-process from the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PksOperation {
+    Check,
+    UpdateTodo,
+    Validate,
+    ListPacks,
+    CheckUnnecessaryDependencies,
+}
+
+pub const PKS_OPERATIONS: [PksOperation; 5] = [
+    PksOperation::Check,
+    PksOperation::UpdateTodo,
+    PksOperation::Validate,
+    PksOperation::ListPacks,
+    PksOperation::CheckUnnecessaryDependencies,
+];
+
+impl PksOperation {
+    /// The name of the equivalent `pks` subcommand
+    pub fn name(&self) -> &'static str {
+        match self {
+            PksOperation::Check => "check",
+            PksOperation::UpdateTodo => "update-todo",
+            PksOperation::Validate => "validate",
+            PksOperation::ListPacks => "list-packs",
+            PksOperation::CheckUnnecessaryDependencies => "check-unnecessary-dependencies",
+        }
+    }
+
+    /// Operations that change files on disk, after which `Pks` needs to be reloaded
+    pub fn is_write(&self) -> bool {
+        matches!(self, PksOperation::UpdateTodo)
+    }
+
+    /// Operations whose output can be limited to the packs beneath a path
+    pub fn supports_scope(&self) -> bool {
+        matches!(
+            self,
+            PksOperation::Check
+                | PksOperation::ListPacks
+                | PksOperation::CheckUnnecessaryDependencies
+        )
+    }
+
+    /// Runs the operation against the project in `absolute_root`, passing each line of output
+    /// to `output` as it is produced. `scope` limits output to the packs at or beneath a tree
+    /// path when the operation supports it.
+    /// This reads the configuration from disk and may parse every included file, so it should
+    /// be run off the UI thread.
+    pub fn run(
+        &self,
+        absolute_root: &Path,
+        scope: Option<&str>,
+        output: &mut dyn FnMut(String),
+    ) -> anyhow::Result<()> {
+        let configuration = packs::packs::configuration::get(absolute_root);
+        let scope = if self.supports_scope() { scope } else { None };
+        match self {
+            PksOperation::Check => check(&configuration, scope, output),
+            PksOperation::UpdateTodo => {
+                packs::packs::update(&configuration)?;
+                output(String::from("Successfully updated package_todo.yml files!"));
+                Ok(())
+            }
+            PksOperation::Validate => {
+                packs::packs::validate(&configuration)?;
+                output(String::from("Packwerk validate succeeded!"));
+                Ok(())
+            }
+            PksOperation::ListPacks => {
+                let mut pack_names: Vec<&String> = configuration
+                    .pack_set
+                    .packs
+                    .iter()
+                    .map(|pack| &pack.name)
+                    .filter(|pack_name| in_scope(scope, pack_name))
+                    .collect();
+                pack_names.sort();
+                pack_names
+                    .iter()
+                    .for_each(|pack_name| output(pack_name.to_string()));
+                Ok(())
+            }
+            PksOperation::CheckUnnecessaryDependencies => {
+                match packs::packs::check_unnecessary_dependencies(&configuration, false) {
+                    Ok(()) => {
+                        output(String::from("No unnecessary dependencies"));
+                        Ok(())
+                    }
+                    Err(e) => unnecessary_dependencies(e, scope, output),
+                }
+            }
+        }
+    }
+}
+
+/// Runs the packs crate's check, on the included files beneath `scope` when there is one
+fn check(
+    configuration: &Configuration,
+    scope: Option<&str>,
+    output: &mut dyn FnMut(String),
+) -> anyhow::Result<()> {
+    let mut files = vec![];
+    if let Some(scope) = scope {
+        files = configuration
+            .included_files
+            .iter()
+            .filter_map(|file| file.strip_prefix(&configuration.absolute_root).ok())
+            .map(|file| file.display().to_string())
+            .filter(|file| in_scope(Some(scope), file))
+            .collect();
+        // the check covers every file when given none
+        if files.is_empty() {
+            output(format!("No files to check in {}", scope));
+            return Ok(());
+        }
+        files.sort();
+    }
+    match packs::packs::check_all(configuration, files) {
+        Ok(()) => {
+            output(String::from("No violations detected!"));
+            Ok(())
+        }
+        Err(e) => {
+            e.to_string()
+                .lines()
+                .for_each(|line| output(line.to_string()));
+            Err(anyhow::anyhow!("Violations found"))
+        }
+    }
+}
+
+fn in_scope(scope: Option<&str>, pack_name: &str) -> bool {
+    match scope {
+        Some(scope) => part_contained_in_other_path(scope, &prepend_dot_to_path(pack_name)),
+        None => true,
+    }
+}
+
+const UNNECESSARY_DEPENDENCY_SEPARATOR: &str = " depends on ";
+
+/// Reports the unnecessary dependencies of the packs beneath `scope` from the error of the
+/// packs crate's check, which lists one "<pack> depends on <dependency> ..." line for each.
+/// An error without such lines is a failure of the check itself.
+fn unnecessary_dependencies(
+    error: anyhow::Error,
+    scope: Option<&str>,
+    output: &mut dyn FnMut(String),
+) -> anyhow::Result<()> {
+    let message = error.to_string();
+    let findings: Vec<(&str, &str)> = message
+        .lines()
+        .filter_map(|line| {
+            line.split_once(UNNECESSARY_DEPENDENCY_SEPARATOR)
+                .map(|(pack_name, _)| (pack_name.trim(), line))
+        })
+        .collect();
+    if findings.is_empty() {
+        return Err(error.context("Failed to check unnecessary dependencies"));
+    }
+    let lines: Vec<&str> = findings
+        .into_iter()
+        .filter(|(pack_name, _)| in_scope(scope, pack_name))
+        .map(|(_, line)| line)
+        .collect();
+    if lines.is_empty() {
+        output(format!(
+            "No unnecessary dependencies in {}",
+            scope.unwrap_or(".")
+        ));
+        return Ok(());
+    }
+    lines.iter().for_each(|line| output(line.to_string()));
+    Err(anyhow::anyhow!("Unnecessary dependencies found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture_root(name: &str) -> PathBuf {
+        PathBuf::from(format!("../tests/fixtures/{}", name))
+            .canonicalize()
+            .expect("Could not canonicalize path")
+    }
+
+    fn run_operation(
+        operation: PksOperation,
+        name: &str,
+        scope: Option<&str>,
+    ) -> (anyhow::Result<()>, Vec<String>) {
+        let mut lines = vec![];
+        let result = operation.run(&fixture_root(name), scope, &mut |line| lines.push(line));
+        (result, lines)
+    }
+
+    #[test]
+    fn test_list_packs() {
+        let (result, lines) = run_operation(PksOperation::ListPacks, "simple_app", None);
+        assert!(result.is_ok());
+        assert_eq!(lines, vec![".", "packs/bar", "packs/baz", "packs/foo"]);
+    }
+
+    #[test]
+    fn test_list_packs_in_scope() {
+        let (result, lines) =
+            run_operation(PksOperation::ListPacks, "simple_app", Some("./packs/foo"));
+        assert!(result.is_ok());
+        assert_eq!(lines, vec!["packs/foo"]);
+    }
+
+    #[test]
+    fn test_check_reports_stale_violations() {
+        let (result, lines) = run_operation(PksOperation::Check, "contains_stale_violations", None);
+        assert_eq!(result.unwrap_err().to_string(), "Violations found");
+        assert_eq!(
+            lines,
+            vec!["There were stale violations found, please run `packs update`"]
+        );
+    }
+
+    #[test]
+    fn test_check_in_scope() {
+        let (result, lines) = run_operation(
+            PksOperation::Check,
+            "contains_stale_violations",
+            Some("./packs/foo"),
+        );
+        // packs/foo declares the dependency recorded in its package_todo.yml
+        assert!(result.is_err());
+        assert_eq!(
+            lines,
+            vec!["There were stale violations found, please run `packs update`"]
+        );
+
+        let (result, lines) =
+            run_operation(PksOperation::Check, "simple_app", Some("./packs/missing"));
+        assert!(result.is_ok());
+        assert_eq!(lines, vec!["No files to check in ./packs/missing"]);
+    }
+
+    #[test]
+    fn test_unnecessary_dependencies_in_scope() {
+        let error = || {
+            anyhow::anyhow!(
+                "List of unnecessary dependencies: \n\
+                packs/foo depends on packs/bar but does not use it\n\
+                packs/foobar depends on packs/baz but does not use it\n\n\
+                Found 2 unnecessary dependencies"
+            )
+        };
+        let report = |scope: Option<&str>| {
+            let mut lines = vec![];
+            let result = unnecessary_dependencies(error(), scope, &mut |line| lines.push(line));
+            (result, lines)
+        };
+
+        let (result, lines) = report(Some("./packs/foo"));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unnecessary dependencies found"
+        );
+        assert_eq!(
+            lines,
+            vec!["packs/foo depends on packs/bar but does not use it"]
+        );
+
+        let (result, lines) = report(None);
+        assert!(result.is_err());
+        assert_eq!(lines.len(), 2);
+
+        let (result, lines) = report(Some("./packs/bar"));
+        assert!(result.is_ok());
+        assert_eq!(lines, vec!["No unnecessary dependencies in ./packs/bar"]);
+    }
+
+    #[test]
+    fn test_unnecessary_dependencies_failure() {
+        let mut lines = vec![];
+        let result = unnecessary_dependencies(
+            anyhow::anyhow!("Could not read packs/foo/package.yml"),
+            None,
+            &mut |line| lines.push(line),
+        );
+        assert_eq!(
+            format!("{:#}", result.unwrap_err()),
+            "Failed to check unnecessary dependencies: Could not read packs/foo/package.yml"
+        );
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_operation_properties() {
+        assert!(PksOperation::UpdateTodo.is_write());
+        assert!(!PksOperation::Check.is_write());
+        assert!(!PksOperation::Validate.supports_scope());
+        assert_eq!(
+            PksOperation::CheckUnnecessaryDependencies.name(),
+            "check-unnecessary-dependencies"
+        );
+    }
+}
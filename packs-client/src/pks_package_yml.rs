@@ -0,0 +1,733 @@
+use std::path::PathBuf;
+
+const DEPENDENCIES_KEY: &str = "dependencies";
+const DEFAULT_ITEM_INDENT: &str = "  ";
+
+/// What a `PackageYmlEdit` changes
+#[derive(Debug, PartialEq, Clone)]
+pub enum PackageYmlChange {
+    AddDependency(String),
+    RemoveDependency(String),
+}
+
+impl PackageYmlChange {
+    pub fn title(&self) -> String {
+        match self {
+            PackageYmlChange::AddDependency(name) => format!("add dependency {}", name),
+            PackageYmlChange::RemoveDependency(name) => format!("remove dependency {}", name),
+        }
+    }
+}
+
+/// A pending change to a pack's `package.yml`, kept so it can be previewed before writing
+#[derive(Debug, PartialEq)]
+pub struct PackageYmlEdit {
+    pub pack_name: String,
+    pub yml_path: PathBuf,
+    pub change: PackageYmlChange,
+    pub original_contents: String,
+    pub updated_contents: String,
+    /// Things that may be wrong with the change, e.g. dependency cycles
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PreviewLineKind {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+impl PackageYmlEdit {
+    /// The updated file with the changed lines marked
+    pub fn preview(&self) -> Vec<(PreviewLineKind, String)> {
+        let original: Vec<&str> = self.original_contents.lines().collect();
+        let updated: Vec<&str> = self.updated_contents.lines().collect();
+        let prefix_len = original
+            .iter()
+            .zip(updated.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix_len = original[prefix_len..]
+            .iter()
+            .rev()
+            .zip(updated[prefix_len..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let unchanged = |line: &&str| (PreviewLineKind::Unchanged, line.to_string());
+        let mut preview: Vec<(PreviewLineKind, String)> =
+            original[..prefix_len].iter().map(unchanged).collect();
+        preview.extend(
+            original[prefix_len..original.len() - suffix_len]
+                .iter()
+                .map(|line| (PreviewLineKind::Removed, line.to_string())),
+        );
+        preview.extend(
+            updated[prefix_len..updated.len() - suffix_len]
+                .iter()
+                .map(|line| (PreviewLineKind::Added, line.to_string())),
+        );
+        preview.extend(
+            original[original.len() - suffix_len..]
+                .iter()
+                .map(unchanged),
+        );
+        preview
+    }
+
+    /// Writes the updated contents, failing if the file changed since the edit was planned
+    pub fn write(&self) -> anyhow::Result<()> {
+        let current_contents = std::fs::read_to_string(&self.yml_path)?;
+        if current_contents != self.original_contents {
+            return Err(anyhow::anyhow!(
+                "{} changed since the edit was previewed",
+                self.yml_path.display()
+            ));
+        }
+        Ok(std::fs::write(&self.yml_path, &self.updated_contents)?)
+    }
+}
+
+/// Returns the updated contents, or None if `dependency_name` is not declared.
+/// The file is edited line by line so comments and formatting are preserved.
+pub fn remove_dependency_from_yml(
+    contents: &str,
+    dependency_name: &str,
+) -> anyhow::Result<Option<String>> {
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let key_index = match dependencies_key_index(contents, &lines)? {
+        Some(key_index) => key_index,
+        None => return Ok(None),
+    };
+
+    let key_line = lines[key_index].clone();
+    let key_line = KeyLine::parse(&key_line);
+    match dependencies_value(&lines, key_index, &key_line)? {
+        DependenciesValue::Null => return Ok(None),
+        DependenciesValue::Flow { end_index } => {
+            let items = flow_items(&lines[key_index..=end_index]);
+            if !items.iter().any(|item| item.name == dependency_name) {
+                return Ok(None);
+            }
+            let remaining: Vec<FlowItem> = items
+                .into_iter()
+                .filter(|item| item.name != dependency_name)
+                .collect();
+            let flow = render_flow(&lines, key_index, end_index, &key_line, &remaining);
+            lines.splice(key_index..=end_index, flow);
+        }
+        DependenciesValue::Block => {
+            let item_indexes = block_item_indexes(&lines, key_index);
+            let remove_index = match item_indexes
+                .iter()
+                .copied()
+                .find(|index| block_item_value(&lines[*index]) == Some(dependency_name))
+            {
+                Some(remove_index) => remove_index,
+                None => return Ok(None),
+            };
+            lines.remove(remove_index);
+            if item_indexes.len() == 1 {
+                lines[key_index] = format!("{}: []{}", key_line.key, key_line.comment);
+            }
+        }
+    }
+    validated(join_lines(lines, contents), dependency_name, false).map(Some)
+}
+
+/// Returns the updated contents, or None if `dependency_name` is already declared.
+/// Block sequences keep their indentation and the new item is inserted in sorted position.
+pub fn add_dependency_to_yml(
+    contents: &str,
+    dependency_name: &str,
+) -> anyhow::Result<Option<String>> {
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let key_index = match dependencies_key_index(contents, &lines)? {
+        Some(key_index) => key_index,
+        None => {
+            let indent = top_level_indent(&lines).to_string();
+            lines.push(format!("{}{}:", indent, DEPENDENCIES_KEY));
+            lines.push(format!(
+                "{}{}- {}",
+                indent, DEFAULT_ITEM_INDENT, dependency_name
+            ));
+            let mut updated = join_lines(lines, contents);
+            if !updated.ends_with('\n') {
+                updated.push('\n');
+            }
+            return validated(updated, dependency_name, true).map(Some);
+        }
+    };
+
+    let key_line = lines[key_index].clone();
+    let key_line = KeyLine::parse(&key_line);
+    // the last line of a flow sequence or null, None for a block sequence
+    let (end_index, mut items) = match dependencies_value(&lines, key_index, &key_line)? {
+        DependenciesValue::Block => (None, Vec::new()),
+        DependenciesValue::Null => (Some(key_index), Vec::new()),
+        DependenciesValue::Flow { end_index } => {
+            (Some(end_index), flow_items(&lines[key_index..=end_index]))
+        }
+    };
+    match end_index {
+        // an empty list or null becomes a block sequence with the one item
+        Some(end_index) if items.is_empty() => {
+            let item = format!(
+                "{}{}- {}",
+                indentation(key_line.key),
+                DEFAULT_ITEM_INDENT,
+                dependency_name
+            );
+            lines.splice(
+                key_index..=end_index,
+                [format!("{}:{}", key_line.key, key_line.comment), item],
+            );
+        }
+        Some(end_index) => {
+            if items.iter().any(|item| item.name == dependency_name) {
+                return Ok(None);
+            }
+            items.push(FlowItem {
+                name: dependency_name.to_string(),
+                comment: String::new(),
+            });
+            let flow = render_flow(&lines, key_index, end_index, &key_line, &items);
+            lines.splice(key_index..=end_index, flow);
+        }
+        None => {
+            let item_indexes = block_item_indexes(&lines, key_index);
+            if item_indexes
+                .iter()
+                .any(|index| block_item_value(&lines[*index]) == Some(dependency_name))
+            {
+                return Ok(None);
+            }
+            let indent = item_indexes
+                .first()
+                .map(|index| indentation(&lines[*index]).to_string())
+                .unwrap_or_else(|| format!("{}{}", indentation(key_line.key), DEFAULT_ITEM_INDENT));
+            let insert_index = item_indexes
+                .iter()
+                .copied()
+                .find(|index| {
+                    block_item_value(&lines[*index]).is_some_and(|name| name > dependency_name)
+                })
+                .or_else(|| item_indexes.last().map(|index| index + 1))
+                .unwrap_or(key_index + 1);
+            lines.insert(insert_index, format!("{}- {}", indent, dependency_name));
+        }
+    }
+    validated(join_lines(lines, contents), dependency_name, true).map(Some)
+}
+
+/// The line of the top-level `dependencies` key, or None if the file does not declare it.
+/// serde_yaml tells whether the key is there, which line it is on is found by comparing the
+/// keys of the top-level lines, so neither `dependencies_foo:` nor a nested key matches.
+fn dependencies_key_index(contents: &str, lines: &[String]) -> anyhow::Result<Option<usize>> {
+    let yml: serde_yaml::Value = serde_yaml::from_str(contents)?;
+    if yml.get(DEPENDENCIES_KEY).is_none() {
+        return Ok(None);
+    }
+    let indent = top_level_indent(lines);
+    lines
+        .iter()
+        .position(|line| {
+            indentation(line) == indent
+                && line.contains(':')
+                && unquote(split_key_line(line).0.trim()) == DEPENDENCIES_KEY
+        })
+        .map(Some)
+        .ok_or_else(|| anyhow::anyhow!("Could not find the line of the {} key", DEPENDENCIES_KEY))
+}
+
+/// A `key: value # comment` line
+struct KeyLine<'a> {
+    /// The key with its indentation
+    key: &'a str,
+    /// The value without the comment, trimmed
+    value: &'a str,
+    /// The comment with the whitespace before it, or ""
+    comment: &'a str,
+}
+
+impl<'a> KeyLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        let (key, value) = split_key_line(line);
+        let (value, comment) = split_comment(value);
+        Self {
+            key,
+            value: value.trim(),
+            comment,
+        }
+    }
+}
+
+/// How the `dependencies` key declares its list
+#[derive(Debug, PartialEq)]
+enum DependenciesValue {
+    /// `- name` lines under the key, or none at all
+    Block,
+    /// `~` or `null`
+    Null,
+    /// `[name, ...]`, ending on the line at `end_index`
+    Flow { end_index: usize },
+}
+
+fn dependencies_value(
+    lines: &[String],
+    key_index: usize,
+    key_line: &KeyLine,
+) -> anyhow::Result<DependenciesValue> {
+    match key_line.value {
+        "" => Ok(DependenciesValue::Block),
+        "~" | "null" | "Null" | "NULL" => Ok(DependenciesValue::Null),
+        value if value.starts_with('[') => {
+            if value.contains(']') {
+                return Ok(DependenciesValue::Flow {
+                    end_index: key_index,
+                });
+            }
+            lines
+                .iter()
+                .enumerate()
+                .skip(key_index + 1)
+                .find(|(_, line)| split_comment(line).0.contains(']'))
+                .map(|(end_index, _)| DependenciesValue::Flow { end_index })
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Could not find the end of the {} list", DEPENDENCIES_KEY)
+                })
+        }
+        value => Err(anyhow::anyhow!(
+            "Expected a list of {}, found {}",
+            DEPENDENCIES_KEY,
+            value
+        )),
+    }
+}
+
+/// A name in a flow sequence, with the comment of its line when it is alone on it
+#[derive(Debug, PartialEq)]
+struct FlowItem {
+    name: String,
+    comment: String,
+}
+
+/// The names of the flow sequence spanning `lines`, the first of which is the key line
+fn flow_items(lines: &[String]) -> Vec<FlowItem> {
+    let mut items = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let line = if index == 0 {
+            split_key_line(line).1
+        } else {
+            line
+        };
+        let (value, comment) = split_comment(line);
+        let names: Vec<&str> = value
+            .split(',')
+            .map(|name| {
+                unquote(
+                    name.trim()
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .trim(),
+                )
+            })
+            .filter(|name| !name.is_empty())
+            .collect();
+        let comment = if index > 0 && names.len() == 1 {
+            comment
+        } else {
+            ""
+        };
+        items.extend(names.into_iter().map(|name| FlowItem {
+            name: name.to_string(),
+            comment: comment.to_string(),
+        }));
+    }
+    items
+}
+
+/// The lines replacing the flow sequence from `key_index` to `end_index`. A sequence on one
+/// line stays on one line, otherwise each item gets a line with the indentation of the
+/// first item.
+fn render_flow(
+    lines: &[String],
+    key_index: usize,
+    end_index: usize,
+    key_line: &KeyLine,
+    items: &[FlowItem],
+) -> Vec<String> {
+    if key_index == end_index || items.is_empty() {
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        return vec![format!(
+            "{}: [{}]{}",
+            key_line.key,
+            names.join(", "),
+            key_line.comment
+        )];
+    }
+    let item_indent = lines[key_index + 1..=end_index]
+        .iter()
+        .find(|line| !matches!(split_comment(line).0.trim(), "" | "]"))
+        .map(|line| indentation(line).to_string())
+        .unwrap_or_else(|| format!("{}{}", indentation(key_line.key), DEFAULT_ITEM_INDENT));
+    let mut flow = vec![format!("{}: [{}", key_line.key, key_line.comment)];
+    flow.extend(items.iter().enumerate().map(|(index, item)| {
+        let separator = if index + 1 < items.len() { "," } else { "" };
+        format!("{}{}{}{}", item_indent, item.name, separator, item.comment)
+    }));
+    let closing_line = &lines[end_index];
+    if split_comment(closing_line).0.trim() == "]" {
+        flow.push(closing_line.clone());
+    } else {
+        flow.push(format!("{}]", indentation(key_line.key)));
+    }
+    flow
+}
+
+/// Fails unless `updated` parses and `dependency_name` is in its dependencies exactly when
+/// `declared`, so a broken edit is never written
+fn validated(updated: String, dependency_name: &str, declared: bool) -> anyhow::Result<String> {
+    let yml: serde_yaml::Value = serde_yaml::from_str(&updated)
+        .map_err(|e| anyhow::anyhow!("The edited package.yml would not parse: {}", e))?;
+    let names: Vec<&str> = match yml.get(DEPENDENCIES_KEY) {
+        None | Some(serde_yaml::Value::Null) => Vec::new(),
+        Some(serde_yaml::Value::Sequence(names)) => {
+            names.iter().filter_map(|name| name.as_str()).collect()
+        }
+        Some(_) => {
+            return Err(anyhow::anyhow!(
+                "The edited package.yml would not have a list of {}",
+                DEPENDENCIES_KEY
+            ))
+        }
+    };
+    if names.contains(&dependency_name) != declared {
+        return Err(anyhow::anyhow!(
+            "The edited package.yml would not {} {}",
+            if declared { "declare" } else { "remove" },
+            dependency_name
+        ));
+    }
+    Ok(updated)
+}
+
+/// The indentation of the first key, which all top-level keys share
+fn top_level_indent(lines: &[String]) -> &str {
+    lines
+        .iter()
+        .find(|line| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---"
+        })
+        .map_or("", |line| indentation(line))
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// The key with its indentation and the value of a `key: value` line
+fn split_key_line(line: &str) -> (&str, &str) {
+    line.split_once(':').unwrap_or((line, ""))
+}
+
+/// Indexes of the `- name` lines of the block sequence under the key at `key_index`
+pub(crate) fn block_item_indexes(lines: &[String], key_index: usize) -> Vec<usize> {
+    let mut indexes = Vec::new();
+    for (index, line) in lines.iter().enumerate().skip(key_index + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !trimmed.starts_with("- ") {
+            break;
+        }
+        indexes.push(index);
+    }
+    indexes
+}
+
+pub(crate) fn block_item_value(line: &str) -> Option<&str> {
+    let item = line.trim_start().strip_prefix("- ")?;
+    Some(unquote(strip_comment(item).trim()))
+}
+
+pub(crate) fn join_lines(lines: Vec<String>, original: &str) -> String {
+    let mut joined = lines.join("\n");
+    if original.ends_with('\n') {
+        joined.push('\n');
+    }
+    joined
+}
+
+fn strip_comment(value: &str) -> &str {
+    split_comment(value).0
+}
+
+/// The value and its comment with the whitespace before it, or ""
+fn split_comment(value: &str) -> (&str, &str) {
+    match value.find(" #") {
+        Some(index) => value.split_at(value[..index].trim_end().len()),
+        None => (value, ""),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_dependency_from_block_sequence() {
+        let contents = "\
+enforce_dependencies: true
+# keep me
+dependencies:
+  - packs/a # first
+  - \"packs/b\"
+owner: Team
+";
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/b")
+                .unwrap()
+                .unwrap(),
+            "\
+enforce_dependencies: true
+# keep me
+dependencies:
+  - packs/a # first
+owner: Team
+"
+        );
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/a")
+                .unwrap()
+                .unwrap(),
+            "\
+enforce_dependencies: true
+# keep me
+dependencies:
+  - \"packs/b\"
+owner: Team
+"
+        );
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/c").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_add_dependency_to_yml() {
+        let contents = "\
+# comment
+dependencies:
+    - packs/a # first
+    - packs/c
+owner: Team
+";
+        assert_eq!(
+            add_dependency_to_yml(contents, "packs/b").unwrap().unwrap(),
+            "\
+# comment
+dependencies:
+    - packs/a # first
+    - packs/b
+    - packs/c
+owner: Team
+"
+        );
+        assert_eq!(add_dependency_to_yml(contents, "packs/a").unwrap(), None);
+        assert_eq!(
+            add_dependency_to_yml("enforce_dependencies: true\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "enforce_dependencies: true\ndependencies:\n  - packs/a\n"
+        );
+        assert_eq!(
+            add_dependency_to_yml("dependencies: []\nowner: Team\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies:\n  - packs/a\nowner: Team\n"
+        );
+    }
+
+    #[test]
+    fn test_add_dependency_to_flow_sequence_or_null() {
+        assert_eq!(
+            add_dependency_to_yml("dependencies: [packs/b] # sorted\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: [packs/b, packs/a] # sorted\n"
+        );
+        assert_eq!(
+            add_dependency_to_yml("dependencies: ~ # none yet\nowner: Team\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: # none yet\n  - packs/a\nowner: Team\n"
+        );
+        assert_eq!(
+            add_dependency_to_yml("dependencies: null\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies:\n  - packs/a\n"
+        );
+        let contents = "\
+dependencies: [
+    packs/a, # first
+    packs/b
+  ]
+owner: Team
+";
+        assert_eq!(
+            add_dependency_to_yml(contents, "packs/c").unwrap().unwrap(),
+            "\
+dependencies: [
+    packs/a, # first
+    packs/b,
+    packs/c
+  ]
+owner: Team
+"
+        );
+        assert_eq!(add_dependency_to_yml(contents, "packs/b").unwrap(), None);
+        assert!(add_dependency_to_yml("dependencies: packs/a\n", "packs/b").is_err());
+    }
+
+    #[test]
+    fn test_preview() {
+        let edit = PackageYmlEdit {
+            pack_name: String::from("packs/a"),
+            yml_path: PathBuf::from("packs/a/package.yml"),
+            change: PackageYmlChange::AddDependency(String::from("packs/b")),
+            original_contents: String::from("dependencies: []\nowner: Team\n"),
+            updated_contents: String::from("dependencies:\n  - packs/b\nowner: Team\n"),
+            warnings: vec![],
+        };
+        assert_eq!(
+            edit.preview(),
+            vec![
+                (PreviewLineKind::Removed, String::from("dependencies: []")),
+                (PreviewLineKind::Added, String::from("dependencies:")),
+                (PreviewLineKind::Added, String::from("  - packs/b")),
+                (PreviewLineKind::Unchanged, String::from("owner: Team")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_last_dependency() {
+        assert_eq!(
+            remove_dependency_from_yml("dependencies:\n- packs/a\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: []\n"
+        );
+        assert_eq!(
+            remove_dependency_from_yml("dependencies: [packs/a, 'packs/b']", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: [packs/b]"
+        );
+    }
+
+    #[test]
+    fn test_remove_dependency_from_flow_sequence() {
+        assert_eq!(
+            remove_dependency_from_yml("dependencies: [packs/a, packs/b] # sorted\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: [packs/b] # sorted\n"
+        );
+        let contents = "\
+dependencies: [ # declared
+  packs/a, # first
+  \"packs/b\",
+  packs/c
+]
+owner: Team
+";
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/c")
+                .unwrap()
+                .unwrap(),
+            "\
+dependencies: [ # declared
+  packs/a, # first
+  packs/b
+]
+owner: Team
+"
+        );
+        assert_eq!(
+            remove_dependency_from_yml("dependencies: [\n  packs/a\n]\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies: []\n"
+        );
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/d").unwrap(),
+            None
+        );
+        assert_eq!(
+            remove_dependency_from_yml("dependencies: ~\n", "packs/a").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validated() {
+        assert!(validated(String::from("dependencies: [packs/a\n"), "packs/a", true).is_err());
+        assert!(validated(String::from("dependencies: packs/a\n"), "packs/a", true).is_err());
+        assert!(validated(String::from("dependencies: [packs/b]\n"), "packs/a", true).is_err());
+        assert!(validated(String::from("dependencies: [packs/a]\n"), "packs/a", false).is_err());
+        assert_eq!(
+            validated(String::from("dependencies: ~\n"), "packs/a", false).unwrap(),
+            "dependencies: ~\n"
+        );
+    }
+
+    #[test]
+    fn test_dependencies_key_is_matched_exactly() {
+        let contents = "\
+dependencies_foo:
+  - packs/a
+metadata:
+  dependencies:
+    - packs/a
+\"dependencies\": # declared
+  - packs/a
+";
+        assert_eq!(
+            remove_dependency_from_yml(contents, "packs/a")
+                .unwrap()
+                .unwrap(),
+            "\
+dependencies_foo:
+  - packs/a
+metadata:
+  dependencies:
+    - packs/a
+\"dependencies\": [] # declared
+"
+        );
+        assert_eq!(
+            add_dependency_to_yml("dependencies_foo: []\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "dependencies_foo: []\ndependencies:\n  - packs/a\n"
+        );
+        assert_eq!(
+            add_dependency_to_yml("  owner: Team\n  dependencies: [packs/b]\n", "packs/a")
+                .unwrap()
+                .unwrap(),
+            "  owner: Team\n  dependencies: [packs/b, packs/a]\n"
+        );
+        assert!(add_dependency_to_yml("dependencies: [packs/a\n", "packs/b").is_err());
+    }
+}
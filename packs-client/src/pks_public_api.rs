@@ -0,0 +1,169 @@
+use crate::pks::ConstantViolation;
+use crate::pks_coupling::public_folder;
+use crate::pks_move_constant::PRIVACY_VIOLATION_TYPE;
+use crate::pks_references::{constant_name_for_path, RubySource};
+use packs::packs::pack::Pack;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A constant defined in the pack's public folder
+#[derive(Debug, PartialEq)]
+pub struct PublicConstant {
+    /// Inferred from the file path, e.g. "::Utilities::Api" for `app/public/utilities/api.rb`
+    pub constant_name: String,
+    /// Relative to the project root
+    pub file: PathBuf,
+    /// Other packs with a file mentioning the constant
+    pub referencing_pack_names: BTreeSet<String>,
+}
+
+impl PublicConstant {
+    pub fn is_referenced(&self) -> bool {
+        !self.referencing_pack_names.is_empty()
+    }
+}
+
+/// A constant outside the public folder that other packs reference anyway
+#[derive(Debug, PartialEq)]
+pub struct PrivateConstant {
+    pub constant_name: String,
+    pub privacy_violation_count: usize,
+    /// Sorted by pack name
+    pub referencing_pack_counts: BTreeMap<String, usize>,
+}
+
+/// The public API of one pack and the private constants that would need promoting to it.
+/// Whether a public constant is referenced is a search for its name in the files of other
+/// packs, see `RubySource::references`, as packwerk only records references that are
+/// violations.
+#[derive(Debug, PartialEq)]
+pub struct PublicApi {
+    pub pack_name: String,
+    /// Relative to the project root
+    pub public_folder: PathBuf,
+    /// Sorted by constant name
+    pub constants: Vec<PublicConstant>,
+    /// Most violations first
+    pub private_constants: Vec<PrivateConstant>,
+}
+
+impl PublicApi {
+    pub fn new(
+        pack: &Pack,
+        absolute_root: &Path,
+        pack_files: &HashMap<String, Arc<Vec<PathBuf>>>,
+        constant_violations: &[Arc<ConstantViolation>],
+    ) -> Self {
+        let public_folder = public_folder(pack);
+        let mut constants: Vec<PublicConstant> = pack_files
+            .get(&pack.name)
+            .map_or(&[][..], |files| files)
+            .iter()
+            .filter(|file| file.extension().is_some_and(|extension| extension == "rb"))
+            .filter_map(|file| {
+                let constant_path = file.strip_prefix(&public_folder).ok()?;
+                Some(PublicConstant {
+                    constant_name: constant_name_for_path(constant_path),
+                    file: file.clone(),
+                    referencing_pack_names: BTreeSet::new(),
+                })
+            })
+            .collect();
+        constants.sort_by(|a, b| a.constant_name.cmp(&b.constant_name));
+
+        if !constants.is_empty() {
+            for (pack_name, files) in pack_files.iter() {
+                if pack_name == &pack.name {
+                    continue;
+                }
+                for file in files.iter() {
+                    let source = match RubySource::read(&absolute_root.join(file)) {
+                        Ok(source) => source,
+                        Err(_) => continue,
+                    };
+                    for constant in constants.iter_mut() {
+                        if source.references(&constant.constant_name) {
+                            constant.referencing_pack_names.insert(pack_name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut private_constants: Vec<PrivateConstant> = constant_violations
+            .iter()
+            .filter(|violation| violation.defining_pack_name == pack.name)
+            .filter_map(|violation| {
+                let privacy_violation_count = violation
+                    .violation_type_counts
+                    .get(PRIVACY_VIOLATION_TYPE)
+                    .copied()
+                    .unwrap_or_default();
+                if privacy_violation_count == 0 {
+                    return None;
+                }
+                Some(PrivateConstant {
+                    constant_name: violation.constant.clone(),
+                    privacy_violation_count,
+                    referencing_pack_counts: violation
+                        .referencing_pack_counts
+                        .iter()
+                        .map(|(pack_name, count)| (pack_name.clone(), *count))
+                        .collect(),
+                })
+            })
+            .collect();
+        private_constants.sort_by(|a, b| {
+            b.privacy_violation_count
+                .cmp(&a.privacy_violation_count)
+                .then(a.constant_name.cmp(&b.constant_name))
+        });
+
+        Self {
+            pack_name: pack.name.clone(),
+            public_folder,
+            constants,
+            private_constants,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_public_api() {
+        let mut pks = fixture_pks("layered_app");
+        assert!(pks.get_public_api("packs/utilities").is_none());
+        let public_api = pks.find_public_api("packs/utilities").unwrap();
+        assert_eq!(
+            public_api.public_folder,
+            PathBuf::from("packs/utilities/app/public")
+        );
+        assert_eq!(
+            public_api.constants,
+            vec![PublicConstant {
+                constant_name: String::from("::Utilities::Api"),
+                file: PathBuf::from("packs/utilities/app/public/utilities/api.rb"),
+                referencing_pack_names: BTreeSet::from([String::from("packs/ui")]),
+            }]
+        );
+        assert_eq!(
+            public_api.private_constants,
+            vec![PrivateConstant {
+                constant_name: String::from("::Utilities"),
+                privacy_violation_count: 1,
+                referencing_pack_counts: BTreeMap::from([(String::from("packs/ui"), 1)]),
+            }]
+        );
+
+        assert_eq!(pks.get_public_api("packs/utilities"), Some(public_api));
+
+        let payments = pks.find_public_api("packs/payments").unwrap();
+        assert!(payments.constants.is_empty());
+        assert!(pks.find_public_api("packs/missing").is_none());
+    }
+}
@@ -0,0 +1,180 @@
+use packs::packs::pack::Pack;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// The text of a Ruby file and the namespaces it opens, to tell which constants it mentions.
+/// packwerk only records the references that are violations, so the others are found by
+/// searching the text instead of parsing it.
+#[derive(Debug)]
+pub struct RubySource {
+    contents: String,
+    /// Names of the modules and classes opened with `module` or `class`, e.g. "Utilities"
+    namespaces: HashSet<String>,
+}
+
+impl RubySource {
+    pub fn new(contents: String) -> Self {
+        let namespaces = contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_start();
+                line.strip_prefix("module ")
+                    .or_else(|| line.strip_prefix("class "))
+            })
+            .filter_map(|declaration| {
+                declaration
+                    .split(|c: char| c.is_whitespace() || c == '<' || c == ';')
+                    .next()
+            })
+            .flat_map(|name| name.split("::"))
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        Self {
+            contents,
+            namespaces,
+        }
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path.display(), e))?;
+        Ok(Self::new(contents))
+    }
+
+    /// Whether the file mentions `constant_name`, e.g. "::Utilities::Api", either fully
+    /// qualified or relative to namespaces it opens, like `Api` inside `module Utilities`.
+    /// Longer names such as `Utilities::ApiClient` or `Utilities::Api::Client` do not count.
+    pub fn references(&self, constant_name: &str) -> bool {
+        let parts: Vec<&str> = constant_name.trim_start_matches("::").split("::").collect();
+        (0..parts.len()).any(|namespace_len| {
+            parts[..namespace_len]
+                .iter()
+                .all(|namespace| self.namespaces.contains(*namespace))
+                && mentions(
+                    &self.contents,
+                    &parts[namespace_len..].join("::"),
+                    namespace_len == 0,
+                )
+        })
+    }
+}
+
+/// Whether `name` appears in `text` as a whole constant. A leading "::" is only allowed when
+/// `name` is fully qualified, otherwise it names a constant in the root namespace.
+fn mentions(text: &str, name: &str, fully_qualified: bool) -> bool {
+    text.match_indices(name).any(|(start, _)| {
+        let before = &text[..start];
+        let after = &text[start + name.len()..];
+        let starts_name = match before.strip_suffix("::") {
+            Some(before) => fully_qualified && !before.ends_with(is_constant_char),
+            None => !before.ends_with(is_constant_char),
+        };
+        starts_name && !after.starts_with(is_constant_char) && !after.starts_with("::")
+    })
+}
+
+fn is_constant_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The constant a file of `pack` defines, with each folder in `app` as a Zeitwerk root, e.g.
+/// "packs/foo/app/services/foo/bar.rb" defines "::Foo::Bar". None for other files.
+pub fn defined_constant_name(pack: &Pack, file: &Path) -> Option<String> {
+    let pack_file = match pack.name.as_str() {
+        "." => file,
+        _ => file.strip_prefix(&pack.relative_path).ok()?,
+    };
+    let mut components = pack_file.components();
+    if components.next()?.as_os_str() != "app" {
+        return None;
+    }
+    components.next()?;
+    let constant_path = components.as_path();
+    if constant_path.extension()? != "rb" {
+        return None;
+    }
+    Some(constant_name_for_path(constant_path))
+}
+
+/// Zeitwerk naming, e.g. "utilities/api_client.rb" is "::Utilities::ApiClient"
+pub fn constant_name_for_path(path: &Path) -> String {
+    path.with_extension("")
+        .components()
+        .map(|component| camelize(&component.as_os_str().to_string_lossy()))
+        .fold(String::new(), |name, part| format!("{}::{}", name, part))
+}
+
+fn camelize(snake_case: &str) -> String {
+    snake_case
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_constant_name_for_path() {
+        assert_eq!(
+            constant_name_for_path(Path::new("utilities/api_client.rb")),
+            "::Utilities::ApiClient"
+        );
+        assert_eq!(constant_name_for_path(Path::new("foo.rb")), "::Foo");
+    }
+
+    #[test]
+    fn test_defined_constant_name() {
+        let packs = fixture_pks("layered_app").get_packs();
+        let pack = packs
+            .iter()
+            .find(|pack| pack.name == "packs/utilities")
+            .unwrap();
+        assert_eq!(
+            defined_constant_name(
+                pack,
+                Path::new("packs/utilities/app/public/utilities/api.rb")
+            ),
+            Some(String::from("::Utilities::Api"))
+        );
+        assert_eq!(
+            defined_constant_name(pack, Path::new("packs/utilities/lib/tasks/foo.rb")),
+            None
+        );
+        assert_eq!(
+            defined_constant_name(pack, Path::new("packs/utilities/app/views/foo.erb")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let source = RubySource::new(String::from(
+            "module Ui\n  def call\n    Utilities::Api.call\n    ::Payments\n  end\nend\n",
+        ));
+        assert!(source.references("::Utilities::Api"));
+        assert!(source.references("::Payments"));
+        assert!(!source.references("::Utilities::ApiClient"));
+        assert!(!source.references("::Api"));
+
+        let longer_names = RubySource::new(String::from(
+            "Utilities::ApiClient.call\nUtilities::Api::Client\nMyUtilities::Api\n",
+        ));
+        assert!(!longer_names.references("::Utilities::Api"));
+
+        let relative = RubySource::new(String::from(
+            "module Utilities\n  class Client < Base\n    Api.call\n  end\nend\n",
+        ));
+        assert!(relative.references("::Utilities::Api"));
+        assert!(!relative.references("::Payments::Api"));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::pks_todo::TodoEntry;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Every recorded violation of one constant from one referencing pack
+#[derive(Debug, PartialEq)]
+pub struct ConstantReference {
+    pub constant_name: String,
+    pub defining_pack_name: String,
+    pub referencing_pack_name: String,
+    pub violation_types: BTreeSet<String>,
+    pub files: BTreeSet<String>,
+}
+
+/// Violations whose constant contains `query`, ignoring case and leading "::".
+/// Exact matches come first, then by constant, defining pack and referencing pack.
+pub fn search_constant_references(entries: &[TodoEntry], query: &str) -> Vec<ConstantReference> {
+    let query = query.trim().trim_start_matches("::").to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+    let mut references: BTreeMap<(&str, &str, &str), ConstantReference> = BTreeMap::new();
+    for entry in entries.iter() {
+        if !entry.constant_name.to_lowercase().contains(&query) {
+            continue;
+        }
+        let reference = references
+            .entry((
+                &entry.constant_name,
+                &entry.defining_pack_name,
+                &entry.referencing_pack_name,
+            ))
+            .or_insert_with(|| ConstantReference {
+                constant_name: entry.constant_name.clone(),
+                defining_pack_name: entry.defining_pack_name.clone(),
+                referencing_pack_name: entry.referencing_pack_name.clone(),
+                violation_types: BTreeSet::new(),
+                files: BTreeSet::new(),
+            });
+        reference
+            .violation_types
+            .insert(entry.violation_type.clone());
+        reference.files.insert(entry.file.clone());
+    }
+    let mut references: Vec<ConstantReference> = references.into_values().collect();
+    // stable, so the BTreeMap order is kept within each group
+    references.sort_by_key(|reference| {
+        reference
+            .constant_name
+            .trim_start_matches("::")
+            .to_lowercase()
+            != query
+    });
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_search_constant_references() {
+        let pks = fixture_pks("layered_app");
+        let references = pks.search_constants("ui::formatter");
+        assert_eq!(
+            references,
+            vec![ConstantReference {
+                constant_name: String::from("::Ui::Formatter"),
+                defining_pack_name: String::from("packs/ui"),
+                referencing_pack_name: String::from("packs/utilities"),
+                violation_types: BTreeSet::from([
+                    String::from("architecture"),
+                    String::from("dependency"),
+                ]),
+                files: BTreeSet::from([String::from("packs/utilities/app/services/utilities.rb")]),
+            }]
+        );
+
+        let references = pks.search_constants("u");
+        assert_eq!(references.len(), 2);
+        // an exact match comes first
+        let references = pks.search_constants("::utilities");
+        assert_eq!(references[0].constant_name, "::Utilities");
+
+        assert!(pks.search_constants("  ").is_empty());
+    }
+}
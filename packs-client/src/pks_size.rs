@@ -0,0 +1,132 @@
+use crate::pks::part_contained_in_other_path;
+use crate::pks_coupling::public_folder;
+use crate::pks_tree_node::prepend_dot_to_path;
+use packs::packs::pack::Pack;
+use std::collections::HashMap;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const COUNTED_EXTENSIONS: [&str; 3] = ["rb", "rake", "erb"];
+
+/// Ruby and ERB files owned by a pack, or by all packs beneath a tree path
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PackSize {
+    pub file_count: usize,
+    pub line_count: usize,
+    /// Files in the pack's public folder
+    pub public_file_count: usize,
+}
+
+impl AddAssign for PackSize {
+    fn add_assign(&mut self, other: Self) {
+        self.file_count += other.file_count;
+        self.line_count += other.line_count;
+        self.public_file_count += other.public_file_count;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PackSizes {
+    /// Key is pack name
+    pub packs: HashMap<String, PackSize>,
+}
+
+impl PackSizes {
+    /// `pack_files` are relative to `absolute_root` and already filtered by the packwerk
+    /// include and exclude globs
+    pub fn new(
+        absolute_root: &Path,
+        packs: &[Arc<Pack>],
+        pack_files: &HashMap<String, Arc<Vec<PathBuf>>>,
+    ) -> Self {
+        let packs = packs
+            .iter()
+            .map(|pack| {
+                let public_folder = public_folder(pack);
+                let mut size = PackSize::default();
+                let files = pack_files.get(&pack.name).map_or(&[][..], |files| files);
+                for file in files.iter().filter(|file| is_counted(file)) {
+                    size.file_count += 1;
+                    size.line_count += count_lines(&absolute_root.join(file));
+                    if file.starts_with(&public_folder) {
+                        size.public_file_count += 1;
+                    }
+                }
+                (pack.name.clone(), size)
+            })
+            .collect();
+        Self { packs }
+    }
+
+    /// The pack's own size, zero for a directory without a package.yml
+    pub fn size_for_pack(&self, pack_name: &str) -> PackSize {
+        self.packs.get(pack_name).copied().unwrap_or_default()
+    }
+
+    /// Sum of the packs at or beneath a tree path, e.g. "./packs"
+    pub fn size_for_path(&self, path: &str) -> PackSize {
+        let mut size = PackSize::default();
+        for (pack_name, pack_size) in self.packs.iter() {
+            if part_contained_in_other_path(path, &prepend_dot_to_path(pack_name)) {
+                size += *pack_size;
+            }
+        }
+        size
+    }
+}
+
+fn is_counted(file: &Path) -> bool {
+    file.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| COUNTED_EXTENSIONS.contains(&extension))
+}
+
+/// Unreadable files count as empty
+fn count_lines(file: &Path) -> usize {
+    match std::fs::read(file) {
+        Ok(contents) if contents.is_empty() => 0,
+        Ok(contents) => {
+            let newlines = contents.iter().filter(|byte| **byte == b'\n').count();
+            if contents.ends_with(b"\n") {
+                newlines
+            } else {
+                newlines + 1
+            }
+        }
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_pack_sizes() {
+        let mut pks = fixture_pks("simple_app");
+        assert_eq!(pks.get_pack_sizes(), None);
+        let pack_sizes = pks.count_pack_sizes();
+        assert_eq!(
+            pack_sizes.size_for_pack("packs/foo"),
+            PackSize {
+                file_count: 3,
+                line_count: 15,
+                public_file_count: 0,
+            }
+        );
+        // node_modules and script are excluded
+        assert_eq!(pack_sizes.size_for_pack(".").file_count, 1);
+        assert_eq!(pack_sizes.size_for_path("./packs").file_count, 6);
+        assert_eq!(pack_sizes.size_for_path(".").file_count, 7);
+    }
+
+    #[test]
+    fn test_public_file_count() {
+        let mut pks = fixture_pks("layered_app");
+        let utilities = pks.count_pack_sizes().size_for_pack("packs/utilities");
+        assert_eq!(utilities.file_count, 2);
+        assert_eq!(utilities.public_file_count, 1);
+    }
+}
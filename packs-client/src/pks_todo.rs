@@ -0,0 +1,241 @@
+use crate::pks_grouping::PackGrouper;
+use packs::packs::checker::ViolationIdentifier;
+use packs::packs::configuration::Configuration;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A single `package_todo.yml` entry: one violation type of one constant in one file
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TodoEntry {
+    pub referencing_pack_name: String,
+    pub defining_pack_name: String,
+    pub constant_name: String,
+    pub violation_type: String,
+    pub file: String,
+}
+
+impl From<&ViolationIdentifier> for TodoEntry {
+    fn from(violation: &ViolationIdentifier) -> Self {
+        Self {
+            referencing_pack_name: violation.referencing_pack_name.clone(),
+            defining_pack_name: violation.defining_pack_name.clone(),
+            constant_name: violation.constant_name.clone(),
+            violation_type: violation.violation_type.clone(),
+            file: violation.file.clone(),
+        }
+    }
+}
+
+/// Todo entries of a single (referencing) pack that are out of sync with the code
+#[derive(Debug, PartialEq, Default)]
+pub struct PackTodoStatus {
+    pub pack_name: String,
+    /// Recorded in `package_todo.yml` but no longer found by the checker; can be deleted
+    pub stale_entries: Vec<TodoEntry>,
+    /// Found by the checker but not recorded in `package_todo.yml`
+    pub unrecorded_entries: Vec<TodoEntry>,
+}
+
+#[derive(Debug, PartialEq, Default, Clone, Copy)]
+pub struct TodoCounts {
+    pub stale_count: usize,
+    pub unrecorded_count: usize,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct TodoStatus {
+    /// Key is pack name, only packs with stale or unrecorded entries are present
+    pub packs: HashMap<String, Arc<PackTodoStatus>>,
+}
+
+impl TodoStatus {
+    pub fn new(recorded: BTreeSet<TodoEntry>, current: BTreeSet<TodoEntry>) -> Self {
+        let mut packs: HashMap<String, PackTodoStatus> = HashMap::new();
+        for entry in recorded.difference(&current) {
+            pack_status_entry(&mut packs, &entry.referencing_pack_name)
+                .stale_entries
+                .push(entry.clone());
+        }
+        for entry in current.difference(&recorded) {
+            pack_status_entry(&mut packs, &entry.referencing_pack_name)
+                .unrecorded_entries
+                .push(entry.clone());
+        }
+
+        Self {
+            packs: packs
+                .into_iter()
+                .map(|(name, status)| (name, Arc::new(status)))
+                .collect(),
+        }
+    }
+
+    /// Runs the packs checker and compares the violations it finds with the ones recorded
+    /// in `package_todo.yml` files. This parses every included file.
+    pub fn check(configuration: &Configuration) -> anyhow::Result<Self> {
+        let recorded = configuration
+            .pack_set
+            .all_violations
+            .iter()
+            .map(TodoEntry::from)
+            .collect();
+        let current = packs::packs::checker::get_all_violations(configuration)?
+            .iter()
+            .map(TodoEntry::from)
+            .collect();
+        Ok(Self::new(recorded, current))
+    }
+
+    /// `check` for the project at `absolute_root`, reading its configuration from disk so
+    /// it can run on another thread than the `Pks`
+    pub fn check_project(absolute_root: &Path) -> anyhow::Result<Self> {
+        Self::check(&packs::packs::configuration::get(absolute_root))
+    }
+
+    /// Statuses of the packs at or beneath `path`, sorted by pack name
+    pub fn pack_statuses_for_path(
+        &self,
+        path: &str,
+        pack_grouper: &PackGrouper,
+    ) -> Vec<Arc<PackTodoStatus>> {
+        let mut statuses: Vec<Arc<PackTodoStatus>> = self
+            .packs
+            .values()
+            .filter(|status| pack_grouper.contains(path, &status.pack_name))
+            .cloned()
+            .collect();
+        statuses.sort_by(|a, b| a.pack_name.cmp(&b.pack_name));
+        statuses
+    }
+}
+
+fn pack_status_entry<'a>(
+    packs: &'a mut HashMap<String, PackTodoStatus>,
+    pack_name: &str,
+) -> &'a mut PackTodoStatus {
+    packs
+        .entry(pack_name.to_string())
+        .or_insert_with(|| PackTodoStatus {
+            pack_name: pack_name.to_string(),
+            ..Default::default()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+    use crate::pks_tree_config::TreeConfig;
+
+    fn entry(referencing: &str, defining: &str, violation_type: &str) -> TodoEntry {
+        TodoEntry {
+            referencing_pack_name: referencing.to_string(),
+            defining_pack_name: defining.to_string(),
+            constant_name: String::from("::Foo"),
+            violation_type: violation_type.to_string(),
+            file: format!("{}/app/services/foo.rb", referencing),
+        }
+    }
+
+    #[test]
+    fn test_todo_status() {
+        let recorded = BTreeSet::from([
+            entry("packs/a", "packs/b", "privacy"),
+            entry("packs/a", "packs/b", "dependency"),
+            entry("packs/nested/c", "packs/b", "privacy"),
+        ]);
+        let current = BTreeSet::from([
+            entry("packs/a", "packs/b", "privacy"),
+            entry("packs/nested/c", "packs/b", "privacy"),
+            entry("packs/nested/c", "packs/a", "dependency"),
+        ]);
+        let status = TodoStatus::new(recorded, current);
+        assert_eq!(status.packs.len(), 2);
+
+        let a = status.packs.get("packs/a").unwrap();
+        assert_eq!(
+            a.stale_entries,
+            vec![entry("packs/a", "packs/b", "dependency")]
+        );
+        assert!(a.unrecorded_entries.is_empty());
+
+        let c = status.packs.get("packs/nested/c").unwrap();
+        assert!(c.stale_entries.is_empty());
+        assert_eq!(
+            c.unrecorded_entries,
+            vec![entry("packs/nested/c", "packs/a", "dependency")]
+        );
+
+        let directory = PackGrouper::new(&TreeConfig::default(), &[]).unwrap();
+        let statuses = status.pack_statuses_for_path("./packs/nested", &directory);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].pack_name, "packs/nested/c");
+        assert_eq!(
+            status.pack_statuses_for_path("./packs", &directory).len(),
+            2
+        );
+        assert_eq!(status.pack_statuses_for_path(".", &directory).len(), 2);
+        assert!(status
+            .pack_statuses_for_path("./packs/b", &directory)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_check_todos_finds_stale_entries() {
+        let mut pks = fixture_pks("contains_stale_violations");
+        assert!(pks.get_todo_status().is_none());
+        let status = pks.check_todos().unwrap();
+        let todo_entry = |referencing: &str,
+                          defining: &str,
+                          constant: &str,
+                          file: &str,
+                          violation_type: &str| TodoEntry {
+            referencing_pack_name: referencing.to_string(),
+            defining_pack_name: defining.to_string(),
+            constant_name: constant.to_string(),
+            violation_type: violation_type.to_string(),
+            file: file.to_string(),
+        };
+
+        // packs/bar records violations in a file that does not exist
+        let bar = status.packs.get("packs/bar").unwrap();
+        assert_eq!(
+            bar.stale_entries,
+            vec![
+                todo_entry(
+                    "packs/bar",
+                    "packs/foo",
+                    "::Foo",
+                    "packs/bar/app/services/newbar.rb",
+                    "dependency"
+                ),
+                todo_entry(
+                    "packs/bar",
+                    "packs/foo",
+                    "::Foo",
+                    "packs/bar/app/services/newbar.rb",
+                    "privacy"
+                ),
+            ]
+        );
+        assert_eq!(bar.unrecorded_entries, vec![]);
+
+        // packs/foo declares its dependency on packs/bar, only the privacy violation remains
+        let foo = status.packs.get("packs/foo").unwrap();
+        assert_eq!(
+            foo.stale_entries,
+            vec![todo_entry(
+                "packs/foo",
+                "packs/bar",
+                "::Bar",
+                "packs/foo/app/services/foo.rb",
+                "dependency"
+            )]
+        );
+        assert_eq!(foo.unrecorded_entries, vec![]);
+
+        assert_eq!(status.packs.len(), 2);
+        assert!(pks.get_todo_status().is_some());
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// Read from the `tree` section of the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TreeConfig {
+    pub grouping: TreeGrouping,
+    pub rules: Vec<GroupingRule>,
+    /// Merge chains of single-child directories, see `compact_tree_nodes`
+    pub compact: bool,
+    pub sort: TreeSort,
+    /// Hide the nodes with fewer than `min_violations`, see `filter_tree_nodes`
+    pub filter: bool,
+    pub min_violations: usize,
+}
+
+impl Default for TreeConfig {
+    fn default() -> Self {
+        Self {
+            grouping: TreeGrouping::default(),
+            rules: Vec::new(),
+            compact: false,
+            sort: TreeSort::default(),
+            filter: false,
+            min_violations: 1,
+        }
+    }
+}
+
+/// Order of the children of each tree node, counts are largest first
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TreeSort {
+    #[default]
+    Name,
+    OutViolations,
+    InViolations,
+    TotalViolations,
+    PackCount,
+}
+
+impl TreeSort {
+    pub fn title(&self) -> &'static str {
+        match self {
+            TreeSort::Name => "name",
+            TreeSort::OutViolations => "out violations",
+            TreeSort::InViolations => "in violations",
+            TreeSort::TotalViolations => "total violations",
+            TreeSort::PackCount => "pack count",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TreeSort::Name => TreeSort::OutViolations,
+            TreeSort::OutViolations => TreeSort::InViolations,
+            TreeSort::InViolations => TreeSort::TotalViolations,
+            TreeSort::TotalViolations => TreeSort::PackCount,
+            TreeSort::PackCount => TreeSort::Name,
+        }
+    }
+}
+
+/// How the packs tree groups packs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TreeGrouping {
+    /// One node per directory, as the packs are laid out on disk
+    #[default]
+    Directory,
+    /// Every pack at the top level
+    Flat,
+    /// Packs under the `owner` from their `package.yml`
+    Owner,
+    /// Packs under the architecture `layer` from their `package.yml`
+    Layer,
+    /// Packs under the first matching `GroupingRule`
+    Rules,
+}
+
+impl TreeGrouping {
+    pub fn title(&self) -> &'static str {
+        match self {
+            TreeGrouping::Directory => "directory",
+            TreeGrouping::Flat => "flat",
+            TreeGrouping::Owner => "owner",
+            TreeGrouping::Layer => "layer",
+            TreeGrouping::Rules => "rules",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TreeGrouping::Directory => TreeGrouping::Flat,
+            TreeGrouping::Flat => TreeGrouping::Owner,
+            TreeGrouping::Owner => TreeGrouping::Layer,
+            TreeGrouping::Layer => TreeGrouping::Rules,
+            TreeGrouping::Rules => TreeGrouping::Directory,
+        }
+    }
+}
+
+/// Puts the packs whose name starts with `pattern` in one group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupingRule {
+    /// Glob matched against the leading path segments of the pack name, e.g.
+    /// "packs/product_services/*" groups each pack by the domain directory it is in
+    pub pattern: String,
+    /// Group name, defaults to the matched segments, e.g. "packs/product_services/payroll"
+    #[serde(default)]
+    pub name: Option<String>,
+}
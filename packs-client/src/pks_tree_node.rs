@@ -1,4 +1,7 @@
 use crate::pks::PathViolations;
+use crate::pks_size::{PackSize, PackSizes};
+use crate::pks_todo::{TodoCounts, TodoStatus};
+use crate::pks_tree_config::TreeSort;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -8,24 +11,68 @@ pub struct PksTreeNode {
     pub node_name: String,
     pub has_package_definition: bool,
     pub children: Option<Vec<PksTreeNode>>,
+    /// Uncontained, the defining pack is beneath this node but the referencing pack is not
     pub in_violation_count: usize,
+    /// Uncontained, the referencing pack is beneath this node but the defining pack is not
     pub out_violation_count: usize,
+    /// Both packs are beneath this node
+    pub contained_in_violation_count: usize,
+    pub contained_out_violation_count: usize,
+    pub stale_todo_count: usize,
+    pub unrecorded_violation_count: usize,
+    /// Rolled up from the packs at or beneath this node
+    pub size: PackSize,
+    /// Packs at or beneath this node
+    pub pack_count: usize,
+}
+
+impl PksTreeNode {
+    /// Uncontained violations in and out, the counts the tree shows
+    pub fn total_violation_count(&self) -> usize {
+        self.in_violation_count + self.out_violation_count
+    }
 }
 
 pub struct PksTreeBuilder {
-    pack_names: Vec<String>,
+    /// Pack name and its tree nodes as (path, node name), root first
+    pack_node_names: Vec<(String, Vec<(String, String)>)>,
     violation_counts: Arc<HashMap<String, Arc<PathViolations>>>,
+    todo_status: Option<Arc<TodoStatus>>,
+    pack_sizes: Option<Arc<PackSizes>>,
     pub children: Vec<PksTreeNode>,
 }
 
 impl PksTreeBuilder {
+    /// Builds one node per directory of the pack names
     pub fn new(
         pack_names: Vec<String>,
         violation_counts: Arc<HashMap<String, Arc<PathViolations>>>,
+        todo_status: Option<Arc<TodoStatus>>,
+        pack_sizes: Option<Arc<PackSizes>>,
+    ) -> Self {
+        let pack_node_names = pack_names
+            .into_iter()
+            .map(|pack_name| {
+                let node_names = pack_name_to_node_names(&pack_name);
+                (pack_name, node_names)
+            })
+            .collect();
+        Self::with_node_names(pack_node_names, violation_counts, todo_status, pack_sizes)
+    }
+
+    /// Builds the nodes given for each pack, e.g. by a `PackGrouper`. Todo counts and sizes
+    /// are rolled up from the packs beneath each node.
+    pub fn with_node_names(
+        pack_node_names: Vec<(String, Vec<(String, String)>)>,
+        violation_counts: Arc<HashMap<String, Arc<PathViolations>>>,
+        todo_status: Option<Arc<TodoStatus>>,
+        pack_sizes: Option<Arc<PackSizes>>,
     ) -> Self {
         let mut builder = Self {
-            pack_names,
+            pack_node_names,
             violation_counts,
+            todo_status,
+            pack_sizes,
             children: Vec::new(),
         };
         builder.build();
@@ -33,60 +80,155 @@ impl PksTreeBuilder {
     }
 
     fn build(&mut self) {
-        for pack_name in self.pack_names.clone() {
-            let pack_name_parts = pack_name_to_node_names(&pack_name);
-            let mut pack_name_parts = pack_name_parts.into_iter().peekable();
-            while let Some((pack_name, name)) = pack_name_parts.next() {
-                self.find_or_create_node(pack_name, name, pack_name_parts.peek().is_none());
-            }
-        }
-    }
-    fn find_or_create_node(&mut self, pack_name: String, node_name: String, leaf: bool) {
-        let dirs = pack_name_to_node_names(&pack_name);
-        let mut dir_parts = dirs.into_iter().peekable();
-        let mut current_nodes = &mut self.children;
-        while let Some((pack_name, _)) = dir_parts.next() {
-            if dir_parts.peek().is_some() {
-                let node: &mut PksTreeNode = current_nodes
-                    .iter_mut()
-                    .find(|node| node.path == pack_name)
-                    .unwrap();
-                if node.children.is_none() {
-                    node.children = Some(Vec::new());
+        for (pack_name, node_names) in self.pack_node_names.iter() {
+            let todo_counts = self
+                .todo_status
+                .as_ref()
+                .and_then(|todo_status| todo_status.packs.get(pack_name))
+                .map(|status| TodoCounts {
+                    stale_count: status.stale_entries.len(),
+                    unrecorded_count: status.unrecorded_entries.len(),
+                })
+                .unwrap_or_default();
+            let size = self
+                .pack_sizes
+                .as_ref()
+                .map(|pack_sizes| pack_sizes.size_for_pack(pack_name))
+                .unwrap_or_default();
+
+            let mut current_nodes = &mut self.children;
+            let mut node_names = node_names.iter().peekable();
+            while let Some((path, node_name)) = node_names.next() {
+                let index = match current_nodes.iter().position(|node| &node.path == path) {
+                    Some(index) => index,
+                    None => {
+                        current_nodes.push(new_node(&self.violation_counts, path, node_name));
+                        current_nodes.len() - 1
+                    }
+                };
+                let node = &mut current_nodes[index];
+                node.stale_todo_count += todo_counts.stale_count;
+                node.unrecorded_violation_count += todo_counts.unrecorded_count;
+                node.size += size;
+                node.pack_count += 1;
+                if node_names.peek().is_none() {
+                    node.has_package_definition = true;
+                    break;
                 }
-                let children: &mut Vec<PksTreeNode> = node.children.as_mut().unwrap();
-                current_nodes = children;
-            }
-        }
-        if let Some(found) = current_nodes.iter_mut().find(|node| node.path == pack_name) {
-            if !found.has_package_definition && leaf {
-                found.has_package_definition = true;
+                current_nodes = node.children.get_or_insert_with(Vec::new);
             }
-            return;
         }
+    }
+}
 
-        let children = if leaf { None } else { Some(Vec::new()) };
-        let vc = self.violation_counts.get(&pack_name);
-        let (in_violation_count, out_violation_count) = match vc {
-            Some(vc) => (
-                vc.uncontained_in_violations_count,
-                vc.uncontained_out_violations_count,
-            ),
-            None => (0, 0),
-        };
-        let new_node = PksTreeNode {
-            path: pack_name,
-            node_name,
-            children,
-            in_violation_count,
-            out_violation_count,
-            has_package_definition: leaf,
+fn new_node(
+    violation_counts: &HashMap<String, Arc<PathViolations>>,
+    path: &str,
+    node_name: &str,
+) -> PksTreeNode {
+    let (
+        in_violation_count,
+        out_violation_count,
+        contained_in_violation_count,
+        contained_out_violation_count,
+    ) = match violation_counts.get(path) {
+        Some(vc) => (
+            vc.uncontained_in_violations_count,
+            vc.uncontained_out_violations_count,
+            vc.contained_in_violations_count,
+            vc.contained_out_violations_count,
+        ),
+        None => (0, 0, 0, 0),
+    };
+    PksTreeNode {
+        path: path.to_string(),
+        node_name: node_name.to_string(),
+        has_package_definition: false,
+        children: None,
+        in_violation_count,
+        out_violation_count,
+        contained_in_violation_count,
+        contained_out_violation_count,
+        stale_todo_count: 0,
+        unrecorded_violation_count: 0,
+        size: PackSize::default(),
+        pack_count: 0,
+    }
+}
+
+/// Merges each directory without a package.yml into its only child directory, e.g.
+/// "product_services" and "payroll" into one "product_services/payroll" node. The merged
+/// node keeps the path and counts of the deepest directory, which already roll up
+/// everything beneath it.
+pub fn compact_tree_nodes(nodes: Vec<PksTreeNode>) -> Vec<PksTreeNode> {
+    nodes.into_iter().map(compact_tree_node).collect()
+}
+
+fn compact_tree_node(mut node: PksTreeNode) -> PksTreeNode {
+    while has_only_child_directory(&node) {
+        let child = node.children.take().unwrap().pop().unwrap();
+        node = PksTreeNode {
+            node_name: format!("{}/{}", node.node_name, child.node_name),
+            ..child
         };
+    }
+    node.children = node.children.map(compact_tree_nodes);
+    node
+}
+
+fn has_only_child_directory(node: &PksTreeNode) -> bool {
+    if node.has_package_definition {
+        return false;
+    }
+    match node.children.as_deref() {
+        Some([child]) => !child.has_package_definition && child.children.is_some(),
+        _ => false,
+    }
+}
 
-        current_nodes.push(new_node);
+fn sort_key(sort: TreeSort, node: &PksTreeNode) -> usize {
+    match sort {
+        TreeSort::Name => 0,
+        TreeSort::OutViolations => node.out_violation_count,
+        TreeSort::InViolations => node.in_violation_count,
+        TreeSort::TotalViolations => node.total_violation_count(),
+        TreeSort::PackCount => node.pack_count,
     }
 }
 
+/// Sorts the children of every node, ties and `TreeSort::Name` by node name
+pub fn sort_tree_nodes(nodes: &mut [PksTreeNode], sort: TreeSort) {
+    nodes.sort_by(|a, b| {
+        sort_key(sort, b)
+            .cmp(&sort_key(sort, a))
+            .then_with(|| a.node_name.cmp(&b.node_name))
+    });
+    for node in nodes.iter_mut() {
+        if let Some(children) = node.children.as_mut() {
+            sort_tree_nodes(children, sort);
+        }
+    }
+}
+
+/// Keeps the nodes with at least `min_violations` in total and the ancestors of the nodes
+/// kept, so every match stays reachable
+pub fn filter_tree_nodes(nodes: Vec<PksTreeNode>, min_violations: usize) -> Vec<PksTreeNode> {
+    nodes
+        .into_iter()
+        .filter_map(|mut node| {
+            let children = node
+                .children
+                .take()
+                .map(|children| filter_tree_nodes(children, min_violations));
+            let has_children = children
+                .as_ref()
+                .is_some_and(|children| !children.is_empty());
+            node.children = children;
+            (has_children || node.total_violation_count() >= min_violations).then_some(node)
+        })
+        .collect()
+}
+
 pub(crate) fn pack_name_to_node_names(
     pack_name: &str,
 ) -> Vec<(String /* pack_name */, String /* name */)> {
@@ -101,7 +243,8 @@ pub(crate) fn pack_name_to_node_names(
     node_names
 }
 
-pub(crate) fn prepend_dot_to_path(path: &str) -> String {
+/// Tree path of a pack name, e.g. "./packs/foo" for "packs/foo"
+pub fn prepend_dot_to_path(path: &str) -> String {
     if path.starts_with('.') {
         path.to_owned()
     } else {
@@ -119,31 +262,55 @@ mod tests {
             String::from("."),
             String::from("packs/product_services/payroll/show_me_the_money"),
         ];
-        let result = PksTreeBuilder::new(pack_names, Arc::new(HashMap::new())).children;
+        let result = PksTreeBuilder::new(pack_names, Arc::new(HashMap::new()), None, None).children;
         let expected = vec![PksTreeNode {
             path: String::from("."),
             node_name: String::from("."),
             has_package_definition: true,
             in_violation_count: 0,
             out_violation_count: 0,
+            contained_in_violation_count: 0,
+            contained_out_violation_count: 0,
+            stale_todo_count: 0,
+            unrecorded_violation_count: 0,
+            size: PackSize::default(),
+            pack_count: 2,
             children: Some(vec![PksTreeNode {
                 path: String::from("./packs"),
                 node_name: String::from("packs"),
                 has_package_definition: false,
                 in_violation_count: 0,
                 out_violation_count: 0,
+                contained_in_violation_count: 0,
+                contained_out_violation_count: 0,
+                stale_todo_count: 0,
+                unrecorded_violation_count: 0,
+                size: PackSize::default(),
+                pack_count: 1,
                 children: Some(vec![PksTreeNode {
                     path: String::from("./packs/product_services"),
                     node_name: String::from("product_services"),
                     has_package_definition: false,
                     in_violation_count: 0,
                     out_violation_count: 0,
+                    contained_in_violation_count: 0,
+                    contained_out_violation_count: 0,
+                    stale_todo_count: 0,
+                    unrecorded_violation_count: 0,
+                    size: PackSize::default(),
+                    pack_count: 1,
                     children: Some(vec![PksTreeNode {
                         path: String::from("./packs/product_services/payroll"),
                         node_name: String::from("payroll"),
                         has_package_definition: false,
                         in_violation_count: 0,
                         out_violation_count: 0,
+                        contained_in_violation_count: 0,
+                        contained_out_violation_count: 0,
+                        stale_todo_count: 0,
+                        unrecorded_violation_count: 0,
+                        size: PackSize::default(),
+                        pack_count: 1,
                         children: Some(vec![PksTreeNode {
                             path: String::from(
                                 "./packs/product_services/payroll/show_me_the_money",
@@ -152,6 +319,12 @@ mod tests {
                             has_package_definition: true,
                             in_violation_count: 0,
                             out_violation_count: 0,
+                            contained_in_violation_count: 0,
+                            contained_out_violation_count: 0,
+                            stale_todo_count: 0,
+                            unrecorded_violation_count: 0,
+                            size: PackSize::default(),
+                            pack_count: 1,
                             children: None,
                         }]),
                     }]),
@@ -161,6 +334,88 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_compact_tree_nodes() {
+        let pack_names = vec![
+            String::from("."),
+            String::from("packs/product_services/payroll/show_me_the_money"),
+            String::from("packs/product_services/payroll/time_off"),
+        ];
+        let tree = PksTreeBuilder::new(pack_names, Arc::new(HashMap::new()), None, None).children;
+        let result = compact_tree_nodes(tree);
+        // the root is a pack, so it stays
+        let root = &result[0];
+        assert_eq!(root.node_name, ".");
+        let merged = &root.children.as_ref().unwrap()[0];
+        assert_eq!(merged.node_name, "packs/product_services/payroll");
+        assert_eq!(merged.path, "./packs/product_services/payroll");
+        let names: Vec<&str> = merged
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|node| node.node_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["show_me_the_money", "time_off"]);
+    }
+
+    fn node_names(nodes: &[PksTreeNode]) -> Vec<&str> {
+        nodes.iter().map(|node| node.node_name.as_str()).collect()
+    }
+
+    fn violations(in_count: usize, out_count: usize) -> Arc<PathViolations> {
+        Arc::new(PathViolations {
+            uncontained_in_violations_count: in_count,
+            uncontained_out_violations_count: out_count,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_sort_and_filter_tree_nodes() {
+        let pack_names = vec![
+            String::from("packs/a"),
+            String::from("packs/b"),
+            String::from("packs/c/d"),
+        ];
+        let violation_counts = HashMap::from([
+            (String::from("./packs/a"), violations(1, 0)),
+            (String::from("./packs/b"), violations(0, 3)),
+            (String::from("./packs/c"), violations(2, 0)),
+            (String::from("./packs/c/d"), violations(2, 0)),
+        ]);
+        let mut tree =
+            PksTreeBuilder::new(pack_names, Arc::new(violation_counts), None, None).children;
+        let packs = |tree: &[PksTreeNode]| -> Vec<String> {
+            let packs = tree[0].children.as_ref().unwrap()[0]
+                .children
+                .as_ref()
+                .unwrap();
+            node_names(packs)
+                .iter()
+                .map(|name| name.to_string())
+                .collect()
+        };
+
+        sort_tree_nodes(&mut tree, TreeSort::OutViolations);
+        assert_eq!(packs(&tree), vec!["b", "a", "c"]);
+        sort_tree_nodes(&mut tree, TreeSort::InViolations);
+        assert_eq!(packs(&tree), vec!["c", "a", "b"]);
+        sort_tree_nodes(&mut tree, TreeSort::Name);
+        assert_eq!(packs(&tree), vec!["a", "b", "c"]);
+
+        let tree = filter_tree_nodes(tree, 2);
+        // "." and "packs" have no violations but stay above the matches
+        assert_eq!(node_names(&tree), vec!["."]);
+        assert_eq!(packs(&tree), vec!["b", "c"]);
+        let c = &tree[0].children.as_ref().unwrap()[0]
+            .children
+            .as_ref()
+            .unwrap()[1];
+        assert_eq!(node_names(c.children.as_ref().unwrap()), vec!["d"]);
+        assert!(filter_tree_nodes(tree, 4).is_empty());
+    }
+
     #[test]
     fn test_pack_name_to_node_names() {
         assert_eq!(
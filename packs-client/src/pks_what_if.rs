@@ -0,0 +1,267 @@
+use crate::pks::{build_path_violations, PackDependentViolation, PathViolations};
+use crate::pks_tree_node::{
+    pack_name_to_node_names, prepend_dot_to_path, PksTreeBuilder, PksTreeNode,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// A change to pack boundaries that only exists in memory
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundaryChange {
+    /// Fold the packs into the pack named `into`, which may be one of them or a new name
+    Merge {
+        pack_names: Vec<String>,
+        into: String,
+    },
+    /// Move the packs beneath `parent`, keeping their last path segment
+    Reparent {
+        pack_names: Vec<String>,
+        parent: String,
+    },
+}
+
+/// A sequence of boundary changes, applied in order, e.g. merge packs and then move the result
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WhatIf {
+    pub changes: Vec<BoundaryChange>,
+}
+
+impl WhatIf {
+    pub fn merge(mut self, pack_names: &[&str], into: &str) -> Self {
+        self.changes.push(BoundaryChange::Merge {
+            pack_names: pack_names.iter().map(|name| name.to_string()).collect(),
+            into: into.to_string(),
+        });
+        self
+    }
+
+    pub fn reparent(mut self, pack_names: &[&str], parent: &str) -> Self {
+        self.changes.push(BoundaryChange::Reparent {
+            pack_names: pack_names.iter().map(|name| name.to_string()).collect(),
+            parent: parent.to_string(),
+        });
+        self
+    }
+
+    /// The name `pack_name` ends up with after every change
+    pub fn rename(&self, pack_name: &str) -> String {
+        self.changes
+            .iter()
+            .fold(pack_name.to_string(), |name, change| match change {
+                BoundaryChange::Merge { pack_names, into } if pack_names.contains(&name) => {
+                    into.clone()
+                }
+                BoundaryChange::Reparent { pack_names, parent } if pack_names.contains(&name) => {
+                    let last_segment = name.rsplit('/').next().unwrap_or(&name);
+                    format!("{}/{}", parent.trim_end_matches('/'), last_segment)
+                }
+                _ => name,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViolationOutcome {
+    /// Referencing and defining packs end up in the same pack
+    Disappears,
+    /// Referencing and defining packs end up sharing a deeper parent path than before
+    BecomesContained,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ViolationChange {
+    /// The violation as it is recorded today
+    pub violation: Arc<PackDependentViolation>,
+    pub outcome: ViolationOutcome,
+    /// Deepest path containing both packs after the change
+    pub contained_in_path: String,
+}
+
+/// Violations, path counts and tree data as they would be after a `WhatIf`
+#[derive(Debug)]
+pub struct WhatIfResult {
+    pub pack_names: Vec<String>,
+    pub violations: Vec<Arc<PackDependentViolation>>,
+    pub path_violations: Arc<HashMap<String, Arc<PathViolations>>>,
+    pub tree: Vec<PksTreeNode>,
+    /// Only violations that disappear or become contained
+    pub violation_changes: Vec<ViolationChange>,
+    pub total_before: usize,
+    pub total_after: usize,
+}
+
+impl WhatIfResult {
+    pub fn new(
+        what_if: &WhatIf,
+        pack_names: &[String],
+        violations: &[Arc<PackDependentViolation>],
+    ) -> Self {
+        let renamed_pack_names: BTreeSet<String> = pack_names
+            .iter()
+            .map(|pack_name| what_if.rename(pack_name))
+            .collect();
+
+        let mut renamed_violations: HashMap<(String, String), PackDependentViolation> =
+            HashMap::new();
+        let mut violation_changes = Vec::new();
+        for violation in violations.iter() {
+            let defining_pack_name = what_if.rename(&violation.defining_pack_name);
+            let referencing_pack_name = what_if.rename(&violation.referencing_pack_name);
+            if defining_pack_name == referencing_pack_name {
+                violation_changes.push(ViolationChange {
+                    violation: violation.clone(),
+                    outcome: ViolationOutcome::Disappears,
+                    contained_in_path: prepend_dot_to_path(&defining_pack_name),
+                });
+                continue;
+            }
+            let before = common_path(
+                &violation.defining_pack_name,
+                &violation.referencing_pack_name,
+            );
+            let after = common_path(&defining_pack_name, &referencing_pack_name);
+            if after.len() > before.len() {
+                violation_changes.push(ViolationChange {
+                    violation: violation.clone(),
+                    outcome: ViolationOutcome::BecomesContained,
+                    contained_in_path: after,
+                });
+            }
+
+            let entry = renamed_violations
+                .entry((defining_pack_name.clone(), referencing_pack_name.clone()))
+                .or_insert_with(|| PackDependentViolation {
+                    defining_pack_name,
+                    referencing_pack_name,
+                    violation_type_counts: HashMap::new(),
+                    constant_counts: HashMap::new(),
+                });
+            for (violation_type, count) in violation.violation_type_counts.iter() {
+                *entry
+                    .violation_type_counts
+                    .entry(violation_type.clone())
+                    .or_default() += count;
+            }
+            for (constant, count) in violation.constant_counts.iter() {
+                *entry.constant_counts.entry(constant.clone()).or_default() += count;
+            }
+        }
+
+        let mut renamed_violations: Vec<Arc<PackDependentViolation>> =
+            renamed_violations.into_values().map(Arc::new).collect();
+        renamed_violations.sort_by(|a, b| {
+            a.referencing_pack_name
+                .cmp(&b.referencing_pack_name)
+                .then(a.defining_pack_name.cmp(&b.defining_pack_name))
+        });
+        let path_violations = Arc::new(build_path_violations(&renamed_violations));
+        let pack_names: Vec<String> = renamed_pack_names.into_iter().collect();
+        let tree =
+            PksTreeBuilder::new(pack_names.clone(), path_violations.clone(), None, None).children;
+
+        Self {
+            pack_names,
+            total_before: violations.iter().map(|v| v.all_violation_counts()).sum(),
+            total_after: renamed_violations
+                .iter()
+                .map(|v| v.all_violation_counts())
+                .sum(),
+            violations: renamed_violations,
+            path_violations,
+            tree,
+            violation_changes,
+        }
+    }
+
+    pub fn get_path_violations_for_path(&self, path: &str) -> Option<Arc<PathViolations>> {
+        self.path_violations.get(path).cloned()
+    }
+
+    pub fn violation_changes_with_outcome(
+        &self,
+        outcome: ViolationOutcome,
+    ) -> impl Iterator<Item = &ViolationChange> {
+        self.violation_changes
+            .iter()
+            .filter(move |change| change.outcome == outcome)
+    }
+}
+
+/// The deepest tree path that contains both packs, e.g. "./packs" for packs/a and packs/b
+fn common_path(pack_name: &str, other_pack_name: &str) -> String {
+    let other_paths: BTreeSet<String> = pack_name_to_node_names(other_pack_name)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    pack_name_to_node_names(pack_name)
+        .into_iter()
+        .map(|(path, _)| path)
+        .rfind(|path| other_paths.contains(path))
+        .unwrap_or_else(|| String::from("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pks::fixture_pks;
+
+    #[test]
+    fn test_rename() {
+        let what_if = WhatIf::default()
+            .merge(&["packs/a", "packs/b"], "packs/ab")
+            .reparent(&["packs/ab", "packs/c"], "packs/group");
+        assert_eq!(what_if.rename("packs/a"), "packs/group/ab");
+        assert_eq!(what_if.rename("packs/c"), "packs/group/c");
+        assert_eq!(what_if.rename("packs/d"), "packs/d");
+    }
+
+    #[test]
+    fn test_merge_removes_violations() {
+        let mut pks = fixture_pks("layered_app");
+        let result =
+            pks.simulate(&WhatIf::default().merge(&["packs/ui", "packs/utilities"], "packs/ui"));
+        assert!(!result.pack_names.contains(&String::from("packs/utilities")));
+        assert_eq!(result.total_before - result.total_after, 3);
+        assert_eq!(
+            result
+                .violation_changes_with_outcome(ViolationOutcome::Disappears)
+                .count(),
+            2
+        );
+        let ui = result.get_path_violations_for_path("./packs/ui");
+        assert!(ui.is_none());
+        // pks data is untouched
+        assert!(pks.get_path_violations_for_path("./packs/ui").is_some());
+    }
+
+    #[test]
+    fn test_reparent_contains_violations() {
+        let mut pks = fixture_pks("layered_app");
+        let result = pks.simulate(
+            &WhatIf::default().reparent(&["packs/orphan", "packs/payments"], "packs/billing"),
+        );
+        let changes: Vec<&ViolationChange> = result
+            .violation_changes_with_outcome(ViolationOutcome::BecomesContained)
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].violation.referencing_pack_name, "packs/orphan");
+        assert_eq!(changes[0].contained_in_path, "./packs/billing");
+        assert_eq!(result.total_before, result.total_after);
+
+        let billing = result
+            .get_path_violations_for_path("./packs/billing")
+            .unwrap();
+        assert_eq!(billing.contained_out_violations_count, 1);
+        assert_eq!(billing.uncontained_out_violations_count, 0);
+        let billing_node = result.tree[0]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|node| node.path == "./packs")
+            .and_then(|packs| packs.children.as_ref())
+            .and_then(|children| children.iter().find(|node| node.path == "./packs/billing"))
+            .unwrap();
+        assert!(!billing_node.has_package_definition);
+    }
+}
@@ -4,30 +4,125 @@ use serde::{
     de::{self, Deserializer, Visitor},
     Deserialize, Serialize,
 };
-use strum::Display;
+use strum::{Display, EnumIter, EnumMessage};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize)]
+use crate::{menu::MenuItem, mode::Mode};
+
+/// Variants with a message are listed in the command palette, the others are sent by the app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize, EnumIter, EnumMessage)]
 pub enum Action {
     Tick,
     Render,
     Resize(u16, u16),
+    #[strum(message = "Suspend the application")]
     Suspend,
     Resume,
+    #[strum(message = "Quit the application")]
     Quit,
+    #[strum(message = "Re-run the check on the Todo and Dependencies screens")]
     Refresh,
     Error(String),
     Help,
     Next,
+    #[strum(message = "Go to the next item")]
     Down,
+    #[strum(message = "Go to the previous item")]
     Up,
+    #[strum(message = "Scroll the violations down a page")]
+    PageDown,
+    #[strum(message = "Scroll the violations up a page")]
+    PageUp,
+    #[strum(message = "Scroll the violations down half a page")]
+    HalfPageDown,
+    #[strum(message = "Scroll the violations up half a page")]
+    HalfPageUp,
+    #[strum(message = "Go to the first item")]
+    First,
+    #[strum(message = "Go to the last item")]
+    Last,
+    #[strum(message = "Close the tree node, or scroll the violations left")]
     Left,
+    #[strum(message = "Open the tree node, or scroll the violations right")]
     Right,
+    #[strum(message = "Show violations leaving the selected path")]
     UncontainedOutViolations,
+    #[strum(message = "Show violations entering the selected path")]
     UncontainedInViolations,
+    #[strum(message = "Show outgoing violations within the selected path")]
     ContainedOutViolations,
+    #[strum(message = "Show incoming violations within the selected path")]
     ContainedInViolations,
+    #[strum(message = "Focus the violations, or their next column")]
     NextTab,
+    #[strum(message = "Focus the packs tree")]
     Escape,
+    #[strum(message = "Sort the violations by the focused column, ascending")]
     SortAscending,
+    #[strum(message = "Sort the violations by the focused column, descending")]
     SortDescending,
+    #[strum(message = "Show a screen")]
+    Menu(MenuItem),
+    PathSelected(String),
+    /// Sent when the background todo check completes, its result is kept by the Todo screen
+    TodoCheckReceived,
+    /// Sent when the Todo screen stores a new todo status in the Pks, so the packs tree
+    /// shows its counts
+    TodoStatusUpdated,
+    #[strum(message = "Run actions on the whole project or the selected path")]
+    ToggleScope,
+    OperationOutput(String),
+    /// Sent when a packs operation completes, with the error message if it failed
+    OperationFinished(Option<String>),
+    PksReloaded,
+    /// Sent when the background count of pack sizes completes, its result is kept by Home
+    PackSizesCounted,
+    /// Sent when the background search for references to a public API completes, its result
+    /// is kept by the Public API screen
+    PublicApiFound,
+    /// Sent for the enter key while a package.yml edit is previewed, to write it
+    ConfirmEdit,
+    /// Sent when the background dependency check completes, its result is kept by the
+    /// Dependencies screen
+    DependenciesChecked,
+    #[strum(message = "Remove the selected unused dependency from package.yml")]
+    RemoveDependency,
+    #[strum(message = "Declare the dependency of the selected violation")]
+    AddDependency,
+    #[strum(message = "Go to the defining pack of the selected violation")]
+    JumpToDefiningPack,
+    #[strum(message = "Go to the referencing pack of the selected violation")]
+    JumpToReferencingPack,
+    #[strum(message = "Return to the previous screen, pack, tab and sort")]
+    NavigateBack,
+    #[strum(message = "Undo going back")]
+    NavigateForward,
+    #[strum(message = "Compute metrics from declared dependencies or violations")]
+    ToggleEdges,
+    #[strum(message = "Group the packs tree by directory, flat, owner, layer or rules")]
+    NextTreeGrouping,
+    #[strum(message = "Merge chains of single-child directories in the tree")]
+    ToggleCompactTree,
+    #[strum(message = "Sort the tree by name, out, in or total violations, or pack count")]
+    NextTreeSort,
+    #[strum(message = "Show only the tree nodes with violations")]
+    ToggleTreeFilter,
+    #[strum(message = "Open a popup")]
+    Mode(Mode),
+    /// Selects a path, e.g. "./packs/foo", in the packs tree
+    #[strum(message = "Go to pack")]
+    SelectPath(String),
+    // Search mode has its own navigation so the screen beneath does not move
+    SearchNext,
+    SearchPrevious,
+    SearchTogglePack,
+    SearchJump,
+    #[strum(message = "Bookmark the path selected in the packs tree")]
+    AddBookmark,
+    BookmarkNext,
+    BookmarkPrevious,
+    BookmarkJump,
+    BookmarkDelete,
+    PaletteNext,
+    PalettePrevious,
+    PaletteRun,
 }
@@ -1,5 +1,8 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
 use packs_client::pks::Pks;
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
@@ -7,10 +10,17 @@ use tokio::sync::mpsc;
 
 use crate::{
     action::Action,
-    components::{constants::Constants, home::Home, Component},
+    components::{
+        actions::Actions, bookmarks::Bookmarks, constants::Constants, dependencies::Dependencies,
+        enforcement::Enforcement, flow::Flow, health::Health, home::Home, layers::Layers,
+        metrics::Metrics, palette::Palette, public_api::PublicApi, search::Search,
+        status_bar::StatusBar, todo::Todo, Component,
+    },
     config::Config,
+    key_chords::KeyChords,
     mode::Mode,
     tui,
+    ui::shared::render_pending_chord,
 };
 
 pub struct App {
@@ -21,25 +31,55 @@ pub struct App {
     pub should_quit: bool,
     pub should_suspend: bool,
     pub mode: Mode,
-    pub last_tick_key_events: Vec<KeyEvent>,
+    pub key_chords: KeyChords,
 }
 
 impl App {
     pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
-        let pks = Pks::new(None);
-        let home = Home::new(pks);
-        let constants = Constants::default();
+        let pks = Rc::new(RefCell::new(Pks::new(None)));
+        let home = Home::new(Rc::clone(&pks));
+        let constants = Constants::new(Rc::clone(&pks));
+        let enforcement = Enforcement::new(Rc::clone(&pks));
+        let layers = Layers::new(Rc::clone(&pks));
+        let todo = Todo::new(Rc::clone(&pks));
+        let actions = Actions::new(Rc::clone(&pks));
+        let dependencies = Dependencies::new(Rc::clone(&pks));
+        let health = Health::new(Rc::clone(&pks));
+        let metrics = Metrics::new(Rc::clone(&pks));
+        let public_api = PublicApi::new(Rc::clone(&pks));
+        let flow = Flow::new(Rc::clone(&pks));
+        let search = Search::new(Rc::clone(&pks));
+        let bookmarks = Bookmarks::new(Rc::clone(&pks));
+        let palette = Palette::new(Rc::clone(&pks));
+        let status_bar = StatusBar::new();
         let config = Config::new()?;
         let mode = Mode::Home;
         Ok(Self {
             tick_rate,
             frame_rate,
-            components: vec![Box::new(home), Box::new(constants)],
+            components: vec![
+                Box::new(home),
+                Box::new(constants),
+                Box::new(enforcement),
+                Box::new(layers),
+                Box::new(todo),
+                Box::new(actions),
+                Box::new(dependencies),
+                Box::new(health),
+                Box::new(metrics),
+                Box::new(public_api),
+                Box::new(flow),
+                // drawn last, over the current screen
+                Box::new(search),
+                Box::new(bookmarks),
+                Box::new(palette),
+                Box::new(status_bar),
+            ],
             should_quit: false,
             should_suspend: false,
+            key_chords: KeyChords::new(Duration::from_millis(config.chord_timeout)),
             config,
             mode,
-            last_tick_key_events: Vec::new(),
         })
     }
 
@@ -64,28 +104,35 @@ impl App {
             component.init(tui.size()?)?;
         }
 
+        if let Some(problem) = self.config.problems.first() {
+            for problem in &self.config.problems {
+                log::warn!("Config problem: {problem}");
+            }
+            action_tx.send(Action::Error(format!(
+                "{} config problem(s), using defaults instead. {} (see `{} config check`)",
+                self.config.problems.len(),
+                problem,
+                env!("CARGO_PKG_NAME")
+            )))?;
+        }
+
         loop {
             if let Some(e) = tui.next().await {
                 match e {
                     tui::Event::Quit => action_tx.send(Action::Quit)?,
                     tui::Event::Tick => action_tx.send(Action::Tick)?,
-                    tui::Event::Render => action_tx.send(Action::Render)?,
+                    tui::Event::Render => {
+                        self.key_chords.expire(Instant::now());
+                        action_tx.send(Action::Render)?
+                    }
                     tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
                     tui::Event::Key(key) => {
                         if let Some(keymap) = self.config.keybindings.get(&self.mode) {
-                            if let Some(action) = keymap.get(&vec![key]) {
+                            if let Some(action) =
+                                self.key_chords.handle_key(keymap, key, Instant::now())
+                            {
                                 log::info!("Got action: {action:?}");
-                                action_tx.send(action.clone())?;
-                            } else {
-                                // If the key was not handled as a single key action,
-                                // then consider it for multi-key combinations.
-                                self.last_tick_key_events.push(key);
-
-                                // Check for multi-key combinations
-                                if let Some(action) = keymap.get(&self.last_tick_key_events) {
-                                    log::info!("Got action: {action:?}");
-                                    action_tx.send(action.clone())?;
-                                }
+                                action_tx.send(action)?;
                             }
                         };
                     }
@@ -103,12 +150,13 @@ impl App {
                     log::debug!("{action:?}");
                 }
                 match action {
-                    Action::Tick => {
-                        self.last_tick_key_events.drain(..);
-                    }
                     Action::Quit => self.should_quit = true,
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
+                    Action::Mode(mode) => {
+                        self.mode = mode;
+                        self.key_chords.clear();
+                    }
                     Action::Resize(w, h) => {
                         tui.resize(Rect::new(0, 0, w, h))?;
                         tui.draw(|f| {
@@ -120,6 +168,7 @@ impl App {
                                         .unwrap();
                                 }
                             }
+                            render_pending_chord(f, &self.key_chords.pending());
                         })?;
                     }
                     Action::Render => {
@@ -132,6 +181,7 @@ impl App {
                                         .unwrap();
                                 }
                             }
+                            render_pending_chord(f, &self.key_chords.pending());
                         })?;
                     }
                     _ => {}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 use crate::utils::version;
 
@@ -24,4 +24,40 @@ pub struct Cli {
         default_value_t = 4.0
     )]
     pub frame_rate: f64,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Work with the configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Report the entries of the configuration files that cannot be used
+    Check,
+    /// Print the key bindings and styles in effect, with the file each one comes from
+    Show {
+        #[arg(short, long, value_enum, default_value_t = ConfigFormat::Json5)]
+        format: ConfigFormat,
+    },
+    /// Write a commented starter config.json5 into the config directory
+    Init {
+        /// Overwrite an existing config.json5
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json5,
+    Toml,
+    Yaml,
 }
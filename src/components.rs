@@ -9,9 +9,22 @@ use crate::{
     tui::{Event, Frame},
 };
 
+pub mod actions;
+pub mod bookmarks;
 pub mod constants;
-mod helpers;
+pub mod dependencies;
+pub mod enforcement;
+pub mod flow;
+pub mod health;
+pub(crate) mod helpers;
 pub mod home;
+pub mod layers;
+pub mod metrics;
+pub mod palette;
+pub mod public_api;
+pub mod search;
+pub mod status_bar;
+pub mod todo;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 /// Implementors of this trait can be registered with the main application loop and will be able to receive events,
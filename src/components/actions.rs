@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_grouping::is_group_path;
+use packs_client::pks_operations::{PksOperation, PKS_OPERATIONS};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+const RUNNING_COLOR: Color = Color::LightBlue;
+const SUCCESS_COLOR: Color = Color::LightGreen;
+const FAILURE_COLOR: Color = Color::Red;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum ActivePanel {
+    #[default]
+    Operations,
+    Log,
+}
+
+/// Runs packs operations in-process, on the whole project or the packs beneath the path
+/// selected in the pack tree, and streams their output into a log pane
+pub struct Actions {
+    command_tx: Option<UnboundedSender<Action>>,
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    path: String,
+    whole_project: bool,
+    active_panel: ActivePanel,
+    selected_operation: usize,
+    running: Option<PksOperation>,
+    log: Vec<Line<'static>>,
+    log_scroll: usize,
+}
+
+impl Actions {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            pks,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            whole_project: false,
+            active_panel: ActivePanel::default(),
+            selected_operation: 0,
+            running: None,
+            log: Vec::new(),
+            log_scroll: 0,
+        }
+    }
+
+    /// Operations scope to directories, so a group node like "owner:payroll" runs them on the
+    /// whole project
+    fn scope(&self) -> Option<String> {
+        if self.whole_project || self.path == "." || is_group_path(&self.path) {
+            None
+        } else {
+            Some(self.path.clone())
+        }
+    }
+
+    fn run_selected_operation(&mut self) -> Option<Action> {
+        if let Some(running) = self.running {
+            return Some(Action::Error(format!(
+                "`{}` is still running",
+                running.name()
+            )));
+        }
+        let tx = self.command_tx.clone()?;
+        let operation = PKS_OPERATIONS[self.selected_operation];
+        let scope = self.scope().filter(|_| operation.supports_scope());
+        let root = self.pks.borrow().absolute_root();
+
+        self.push_log_line(Line::from(Span::styled(
+            match &scope {
+                Some(scope) => format!("$ pks {} ({})", operation.name(), scope),
+                None => format!("$ pks {}", operation.name()),
+            },
+            Style::default()
+                .fg(RUNNING_COLOR)
+                .add_modifier(Modifier::BOLD),
+        )));
+        self.running = Some(operation);
+        log::info!("Running {}", operation.name());
+
+        tokio::task::spawn_blocking(move || {
+            let result = operation.run(&root, scope.as_deref(), &mut |line| {
+                let _ = tx.send(Action::OperationOutput(line));
+            });
+            let _ = tx.send(Action::OperationFinished(
+                result.err().map(|e| e.to_string()),
+            ));
+        });
+        None
+    }
+
+    fn finish_operation(&mut self, error: Option<String>) -> Option<Action> {
+        let operation = self.running.take()?;
+        let line = match error {
+            Some(error) => Span::styled(
+                format!("✗ {} failed: {}", operation.name(), error),
+                Style::default().fg(FAILURE_COLOR),
+            ),
+            None => Span::styled(
+                format!("✓ {} finished", operation.name()),
+                Style::default().fg(SUCCESS_COLOR),
+            ),
+        };
+        self.push_log_line(Line::from(line));
+        self.push_log_line(Line::from(""));
+
+        if operation.is_write() {
+            self.pks.borrow_mut().reload();
+            return Some(Action::PksReloaded);
+        }
+        None
+    }
+
+    /// Appends a line, following the output if the log was scrolled to the bottom
+    fn push_log_line(&mut self, line: Line<'static>) {
+        let following = self.log_scroll + 1 >= self.log.len();
+        self.log.push(line);
+        if following {
+            self.log_scroll = self.log.len().saturating_sub(1);
+        }
+    }
+
+    fn render_operations(&mut self, f: &mut Frame, area: Rect) {
+        let scope = match self.scope() {
+            Some(scope) => scope,
+            None => String::from("whole project"),
+        };
+        let rows = PKS_OPERATIONS.iter().map(|operation| {
+            let applies_to = if operation.supports_scope() {
+                scope.clone()
+            } else {
+                String::from("whole project")
+            };
+            let style = if self.running == Some(*operation) {
+                Style::default().fg(RUNNING_COLOR)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(operation.name()),
+                Cell::from(applies_to).style(Style::default().fg(Color::DarkGray)),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows, vec![Constraint::Min(32), Constraint::Min(10)])
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(match self.active_panel {
+                        ActivePanel::Operations => Style::default().fg(Color::Yellow),
+                        ActivePanel::Log => Style::default(),
+                    })
+                    .title(format!("operations: {} (w toggles scope)", scope)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_operation));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_log(&mut self, f: &mut Frame, area: Rect) {
+        self.log_scroll = self.log_scroll.min(self.log.len().saturating_sub(1));
+        let title = match self.running {
+            Some(operation) => format!("output (running {}...)", operation.name()),
+            None => String::from("output"),
+        };
+        let paragraph = Paragraph::new(self.log.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(match self.active_panel {
+                        ActivePanel::Operations => Style::default(),
+                        ActivePanel::Log => Style::default().fg(Color::Yellow),
+                    })
+                    .title(title),
+            )
+            .scroll((self.log_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+}
+
+impl Component for Actions {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => self.path = path,
+            Action::OperationOutput(line) => self.push_log_line(Line::from(line)),
+            Action::OperationFinished(error) => return Ok(self.finish_operation(error)),
+            _ if self.menu_item != MenuItem::Actions => {}
+            Action::Down => match self.active_panel {
+                ActivePanel::Operations => {
+                    self.selected_operation =
+                        (self.selected_operation + 1).min(PKS_OPERATIONS.len() - 1);
+                }
+                ActivePanel::Log => self.log_scroll += 1,
+            },
+            Action::Up => match self.active_panel {
+                ActivePanel::Operations => {
+                    self.selected_operation = self.selected_operation.saturating_sub(1);
+                }
+                ActivePanel::Log => self.log_scroll = self.log_scroll.saturating_sub(1),
+            },
+            Action::Right => match self.active_panel {
+                ActivePanel::Operations => return Ok(self.run_selected_operation()),
+                ActivePanel::Log => {}
+            },
+            Action::NextTab => self.active_panel = ActivePanel::Log,
+            Action::Escape => self.active_panel = ActivePanel::Operations,
+            Action::ToggleScope => self.whole_project = !self.whole_project,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Actions {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Actions), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(chunks[1]);
+        self.render_operations(f, layout[0]);
+        self.render_log(f, layout[1]);
+        Ok(())
+    }
+}
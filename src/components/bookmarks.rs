@@ -0,0 +1,346 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use packs_client::pks::Pks;
+use ratatui::{prelude::*, widgets::*};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::mode::Mode;
+use crate::ui::shared::centered_rect;
+use crate::utils::get_data_dir;
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    /// Path in the packs tree, e.g. "./packs/foo"
+    pub path: String,
+}
+
+/// Named packs tree paths of the current project, saved in the data dir and listed in a
+/// popup over the current screen while in `Mode::Bookmarks`. Typing filters the list by
+/// name, or names the bookmark being added.
+pub struct Bookmarks {
+    command_tx: Option<UnboundedSender<Action>>,
+    /// Bookmarks are kept per project, keyed by its root
+    project_root: String,
+    /// Holds the bookmarks file
+    data_dir: PathBuf,
+    active: bool,
+    /// Path selected in the packs tree, bookmarked by `Action::AddBookmark`
+    path: String,
+    bookmarks: Vec<Bookmark>,
+    input: String,
+    /// The input is the name of a new bookmark instead of a filter
+    naming: bool,
+    selected_bookmark: usize,
+}
+
+impl Bookmarks {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        let project_root = pks.borrow().absolute_root().display().to_string();
+        Self::for_project(project_root, get_data_dir())
+    }
+
+    fn for_project(project_root: String, data_dir: PathBuf) -> Self {
+        let bookmarks = read_bookmarks_file(&data_dir)
+            .map(|mut projects| projects.remove(&project_root).unwrap_or_default())
+            .unwrap_or_else(|e| {
+                log::warn!("Could not read bookmarks: {:?}", e);
+                Vec::new()
+            });
+        Self {
+            command_tx: None,
+            project_root,
+            data_dir,
+            active: false,
+            path: String::from("."),
+            bookmarks,
+            input: String::new(),
+            naming: false,
+            selected_bookmark: 0,
+        }
+    }
+
+    fn filtered_bookmarks(&self) -> Vec<&Bookmark> {
+        let filter = self.input.to_lowercase();
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.name.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    /// Opens the popup to name a bookmark of the selected path, defaulting to its last segment
+    fn start_naming(&mut self) -> Result<()> {
+        self.naming = true;
+        self.input = self
+            .path
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Mode(Mode::Bookmarks))?;
+        }
+        Ok(())
+    }
+
+    /// Adds the named bookmark, replacing one with the same name
+    fn add_bookmark(&mut self) -> Result<()> {
+        let name = self.input.trim().to_string();
+        if name.is_empty() {
+            return Ok(());
+        }
+        self.bookmarks.retain(|bookmark| bookmark.name != name);
+        self.bookmarks.push(Bookmark {
+            name,
+            path: self.path.clone(),
+        });
+        self.bookmarks.sort_by(|a, b| a.name.cmp(&b.name));
+        self.save()?;
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Mode(Mode::Home))?;
+        }
+        Ok(())
+    }
+
+    fn delete_bookmark(&mut self) -> Result<()> {
+        let name = match self.filtered_bookmarks().get(self.selected_bookmark) {
+            Some(bookmark) => bookmark.name.clone(),
+            None => return Ok(()),
+        };
+        self.bookmarks.retain(|bookmark| bookmark.name != name);
+        self.save()
+    }
+
+    /// Leaves bookmarks mode and selects the bookmarked path in the packs tree
+    fn jump_to_bookmark(&mut self) -> Result<()> {
+        let path = match self.filtered_bookmarks().get(self.selected_bookmark) {
+            Some(bookmark) => bookmark.path.clone(),
+            None => return Ok(()),
+        };
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Mode(Mode::Home))?;
+            tx.send(Action::Menu(MenuItem::Home))?;
+            tx.send(Action::SelectPath(path))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the bookmarks of this project, keeping those of the other projects
+    fn save(&self) -> Result<()> {
+        let mut projects = read_bookmarks_file(&self.data_dir)?;
+        if self.bookmarks.is_empty() {
+            projects.remove(&self.project_root);
+        } else {
+            projects.insert(self.project_root.clone(), self.bookmarks.clone());
+        }
+        std::fs::create_dir_all(&self.data_dir)?;
+        std::fs::write(
+            self.data_dir.join(BOOKMARKS_FILE),
+            serde_json::to_string_pretty(&projects)?,
+        )?;
+        Ok(())
+    }
+}
+
+impl Component for Bookmarks {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.active
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            _ => return Ok(None),
+        }
+        if !self.naming {
+            self.selected_bookmark = 0;
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Mode(mode) => {
+                self.active = mode == Mode::Bookmarks;
+                if !self.active {
+                    self.naming = false;
+                    self.input.clear();
+                }
+            }
+            Action::PathSelected(path) => self.path = path,
+            Action::AddBookmark if !self.active => self.start_naming()?,
+            _ if !self.active => {}
+            Action::BookmarkNext => self.selected_bookmark += 1,
+            Action::BookmarkPrevious => {
+                self.selected_bookmark = self.selected_bookmark.saturating_sub(1);
+            }
+            Action::BookmarkJump if self.naming => {
+                if let Err(e) = self.add_bookmark() {
+                    return Ok(Some(Action::Error(format!(
+                        "Could not save bookmark: {:?}",
+                        e
+                    ))));
+                }
+            }
+            Action::BookmarkJump => self.jump_to_bookmark()?,
+            Action::BookmarkDelete if !self.naming => {
+                if let Err(e) = self.delete_bookmark() {
+                    return Ok(Some(Action::Error(format!(
+                        "Could not delete bookmark: {:?}",
+                        e
+                    ))));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, _area: Rect) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        let bookmark_count = self.filtered_bookmarks().len();
+        self.selected_bookmark = self.selected_bookmark.min(bookmark_count.saturating_sub(1));
+        let bookmarks = self.filtered_bookmarks();
+        let area = centered_rect(f.size(), 60, 50);
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let (title, help) = if self.naming {
+            (
+                format!("bookmark {}", self.path),
+                "(esc) cancel (enter) save",
+            )
+        } else {
+            (
+                String::from("jump to bookmark"),
+                "(esc) close (enter) jump (ctrl-d) delete",
+            )
+        };
+        let input = Paragraph::new(format!("{}█", self.input)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(title)
+                .title(block::Title::from(help).alignment(Alignment::Right)),
+        );
+        f.render_widget(input, layout[0]);
+
+        let rows = bookmarks.iter().map(|bookmark| {
+            Row::new(vec![
+                Cell::from(bookmark.name.clone()),
+                Cell::from(bookmark.path.clone()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![Constraint::Percentage(40), Constraint::Percentage(60)],
+        )
+        .header(Row::new(vec!["name", "path"]).bold().height(1))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("bookmarks ({})", bookmarks.len())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default();
+        if !self.naming {
+            table_state.select(Some(self.selected_bookmark));
+        }
+        f.render_stateful_widget(table, layout[1], &mut table_state);
+        Ok(())
+    }
+}
+
+/// Bookmarks of every project, keyed by project root. Empty before the first bookmark.
+fn read_bookmarks_file(data_dir: &Path) -> Result<BTreeMap<String, Vec<Bookmark>>> {
+    let path = data_dir.join(BOOKMARKS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn bookmark(name: &str, path: &str) -> Bookmark {
+        Bookmark {
+            name: name.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bookmarks_are_kept_per_project() {
+        let data_dir =
+            std::env::temp_dir().join(format!("packs-tui-bookmarks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&data_dir);
+
+        let mut first = Bookmarks::for_project(String::from("/projects/first"), data_dir.clone());
+        assert!(first.bookmarks.is_empty());
+        first.path = String::from("./packs/foo");
+        first.input = String::from("foo");
+        first.add_bookmark().unwrap();
+        first.path = String::from("owner:payroll");
+        first.input = String::from("payroll");
+        first.add_bookmark().unwrap();
+
+        let mut second = Bookmarks::for_project(String::from("/projects/second"), data_dir.clone());
+        assert!(second.bookmarks.is_empty());
+        second.path = String::from("./packs/bar");
+        second.input = String::from("bar");
+        second.add_bookmark().unwrap();
+
+        let first = Bookmarks::for_project(String::from("/projects/first"), data_dir.clone());
+        assert_eq!(
+            first.bookmarks,
+            vec![
+                bookmark("foo", "./packs/foo"),
+                bookmark("payroll", "owner:payroll"),
+            ]
+        );
+        let mut second = Bookmarks::for_project(String::from("/projects/second"), data_dir.clone());
+        assert_eq!(second.bookmarks, vec![bookmark("bar", "./packs/bar")]);
+
+        // deleting the last bookmark of a project drops the project from the file
+        second.delete_bookmark().unwrap();
+        let projects = read_bookmarks_file(&data_dir).unwrap();
+        assert_eq!(
+            projects.keys().collect::<Vec<&String>>(),
+            vec!["/projects/first"]
+        );
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
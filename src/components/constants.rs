@@ -1,34 +1,291 @@
-use std::time::Instant;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use color_eyre::eyre::Result;
+use packs_client::pks::{ConstantViolation, Pks};
+use packs_client::pks_move_constant::{MoveCandidate, MoveConstantPlan};
 use ratatui::{prelude::*, widgets::*};
 
-use super::Component;
-use crate::{action::Action, tui::Frame};
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::components::helpers::violations_display::{
+    VIOLATION_HEADER_FULL_TITLES, VIOLATION_TYPES,
+};
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Constants {}
+const BETTER_COLOR: Color = Color::LightGreen;
+const WORSE_COLOR: Color = Color::Red;
 
-impl Default for Constants {
-    fn default() -> Self {
-        Self::new()
-    }
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum ActivePanel {
+    #[default]
+    Constants,
+    Candidates,
+}
+
+/// Constants with violations defined beneath the path selected in the pack tree, and a
+/// planner ranking the packs the selected constant could move to
+pub struct Constants {
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    path: String,
+    active_panel: ActivePanel,
+    selected_constant: usize,
+    selected_candidate: usize,
+    plan: Option<MoveConstantPlan>,
 }
 
 impl Constants {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            active_panel: ActivePanel::default(),
+            selected_constant: 0,
+            selected_candidate: 0,
+            plan: None,
+        }
+    }
+
+    fn constant_violations(&self) -> Vec<Arc<ConstantViolation>> {
+        let pack_grouper = self.pks.borrow_mut().get_pack_grouper();
+        self.pks
+            .borrow_mut()
+            .get_constant_violations()
+            .iter()
+            .filter(|violation| pack_grouper.contains(&self.path, &violation.defining_pack_name))
+            .cloned()
+            .collect()
+    }
+
+    /// Plans the move for the selected constant, reusing the last plan if it is the same one
+    fn update_plan(&mut self, constant: Option<&Arc<ConstantViolation>>) {
+        let constant = match constant {
+            Some(constant) => constant,
+            None => {
+                self.plan = None;
+                return;
+            }
+        };
+        let is_current = self.plan.as_ref().is_some_and(|plan| {
+            plan.constant_name == constant.constant
+                && plan.defining_pack_name == constant.defining_pack_name
+        });
+        if !is_current {
+            self.plan = Some(
+                self.pks
+                    .borrow_mut()
+                    .plan_constant_move(&constant.constant, &constant.defining_pack_name),
+            );
+            self.selected_candidate = 0;
+        }
+    }
+
+    fn render_constants(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        constants: &[Arc<ConstantViolation>],
+    ) {
+        let header = Row::new(vec!["constant", "defining pack", "violations"])
+            .bold()
+            .height(1);
+        let rows = constants.iter().map(|constant| {
+            Row::new(vec![
+                Cell::from(constant.constant.clone()),
+                Cell::from(constant.defining_pack_name.clone()),
+                Cell::from(constant.count.to_string()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(50),
+                Constraint::Percentage(35),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(match self.active_panel {
+                    ActivePanel::Constants => Style::default().fg(Color::Yellow),
+                    ActivePanel::Candidates => Style::default(),
+                })
+                .title(format!("constants: {} ({})", self.path, constants.len())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_constant));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_candidates(&mut self, f: &mut Frame, area: Rect) {
+        let plan = match &self.plan {
+            Some(plan) => plan,
+            None => {
+                f.render_widget(
+                    Block::default().borders(Borders::ALL).title("move to"),
+                    area,
+                );
+                return;
+            }
+        };
+        self.selected_candidate = self
+            .selected_candidate
+            .min(plan.candidates.len().saturating_sub(1));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+
+        let mut header_cells = vec![String::from("pack"), String::from("total")];
+        header_cells.extend(
+            VIOLATION_HEADER_FULL_TITLES[2..]
+                .iter()
+                .map(|t| t.to_string()),
+        );
+        let header = Row::new(header_cells).bold().height(1);
+        let rows = plan.candidates.iter().map(|candidate| {
+            let mut cells = vec![
+                Cell::from(candidate.pack_name.clone()),
+                build_total_cell(candidate.total, plan.current_total),
+            ];
+            cells.extend(VIOLATION_TYPES.iter().map(|violation_type| {
+                Cell::from(
+                    candidate
+                        .count_for_violation_type(violation_type)
+                        .to_string(),
+                )
+            }));
+            Row::new(cells)
+        });
+        let mut widths = vec![Constraint::Min(20), Constraint::Length(10)];
+        widths.extend(VIOLATION_TYPES.iter().map(|_| Constraint::Length(8)));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(match self.active_panel {
+                        ActivePanel::Constants => Style::default(),
+                        ActivePanel::Candidates => Style::default().fg(Color::Yellow),
+                    })
+                    .title(format!(
+                        "move {} from {} ({} violations{})",
+                        plan.constant_name,
+                        plan.defining_pack_name,
+                        plan.current_total,
+                        if plan.private { ", private" } else { "" }
+                    )),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_candidate));
+        f.render_stateful_widget(table, layout[0], &mut table_state);
+
+        f.render_widget(
+            build_pack_changes(plan.candidates.get(self.selected_candidate)),
+            layout[1],
+        );
     }
 }
 
 impl Component for Constants {
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_constant = 0;
+            }
+            Action::PksReloaded => self.plan = None,
+            _ if self.menu_item != MenuItem::Constants => {}
+            Action::Down => match self.active_panel {
+                ActivePanel::Constants => self.selected_constant += 1,
+                ActivePanel::Candidates => self.selected_candidate += 1,
+            },
+            Action::Up => match self.active_panel {
+                ActivePanel::Constants => {
+                    self.selected_constant = self.selected_constant.saturating_sub(1);
+                }
+                ActivePanel::Candidates => {
+                    self.selected_candidate = self.selected_candidate.saturating_sub(1);
+                }
+            },
+            Action::NextTab => self.active_panel = ActivePanel::Candidates,
+            Action::Escape => self.active_panel = ActivePanel::Constants,
+            _ => {}
+        }
         Ok(None)
     }
 
-    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) -> Result<()> {
-        // let block = Block::default().title("constants").title_alignment(Alignment::Right);
-        // f.render_widget(block, rect);
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Constants {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Constants), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(chunks[1]);
+        let constants = self.constant_violations();
+        self.selected_constant = self
+            .selected_constant
+            .min(constants.len().saturating_sub(1));
+        self.update_plan(constants.get(self.selected_constant));
+        self.render_constants(f, layout[0], &constants);
+        self.render_candidates(f, layout[1]);
         Ok(())
     }
 }
+
+fn build_total_cell<'a>(total: usize, current_total: usize) -> Cell<'a> {
+    let delta = total as isize - current_total as isize;
+    Cell::from(format!("{} ({:+})", total, delta)).style(Style::default().fg(delta_color(delta)))
+}
+
+fn delta_color(delta: isize) -> Color {
+    match delta {
+        d if d < 0 => BETTER_COLOR,
+        d if d > 0 => WORSE_COLOR,
+        _ => Color::White,
+    }
+}
+
+fn build_pack_changes<'a>(candidate: Option<&MoveCandidate>) -> Table<'a> {
+    let header = Row::new(vec!["pack", "out", "in", "change"])
+        .bold()
+        .height(1);
+    let changes = candidate.map_or(&[][..], |candidate| candidate.pack_changes.as_slice());
+    let rows = changes.iter().map(|change| {
+        let delta = change.delta();
+        Row::new(vec![
+            Cell::from(change.pack_name.clone()),
+            Cell::from(format!("{} → {}", change.out_before, change.out_after)),
+            Cell::from(format!("{} → {}", change.in_before, change.in_after)),
+            Cell::from(format!("{:+}", delta)).style(Style::default().fg(delta_color(delta))),
+        ])
+    });
+    Table::new(
+        rows,
+        vec![
+            Constraint::Min(20),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("per pack violations"),
+    )
+}
@@ -0,0 +1,317 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use packs_client::pks::Pks;
+use packs_client::pks_dependencies::{Dependencies as ProjectDependencies, PackDependencies};
+use packs_client::pks_package_yml::PackageYmlEdit;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::components::helpers::package_yml_edit::{
+    confirm_edit_key, render_package_yml_edit, update_pending_edit,
+};
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+const UNUSED_COLOR: Color = Color::Gray;
+const UNDECLARED_COLOR: Color = Color::Red;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum ActivePanel {
+    #[default]
+    Packs,
+    UnusedDependencies,
+}
+
+/// Declared dependencies that are never referenced and referenced packs that are not declared,
+/// for the packs beneath the path selected in the pack tree
+pub struct Dependencies {
+    command_tx: Option<UnboundedSender<Action>>,
+    pks: Rc<RefCell<Pks>>,
+    /// Receives the result of the running check, announced by `Action::DependenciesChecked`
+    running_check: Option<mpsc::Receiver<Result<ProjectDependencies, String>>>,
+    menu_item: MenuItem,
+    path: String,
+    active_panel: ActivePanel,
+    selected_pack: usize,
+    selected_dependency: usize,
+    /// The removal of an unused dependency, previewed until it is written or cancelled
+    pending_edit: Option<PackageYmlEdit>,
+}
+
+impl Dependencies {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            pks,
+            running_check: None,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            active_panel: ActivePanel::default(),
+            selected_pack: 0,
+            selected_dependency: 0,
+            pending_edit: None,
+        }
+    }
+
+    /// Checks the dependencies in the background, as it reads the files of every pack
+    fn check_dependencies(&mut self) -> Option<Action> {
+        if self.running_check.is_some() {
+            return None;
+        }
+        let tx = self.command_tx.clone()?;
+        let checker = self.pks.borrow_mut().dependencies_checker();
+        let (result_tx, result_rx) = mpsc::channel();
+        self.running_check = Some(result_rx);
+        log::info!("Checking declared dependencies");
+
+        tokio::task::spawn_blocking(move || {
+            let result = checker().map_err(|e| format!("{:?}", e));
+            let _ = result_tx.send(result);
+            let _ = tx.send(Action::DependenciesChecked);
+        });
+        None
+    }
+
+    fn finish_check(&mut self) -> Option<Action> {
+        let result = self.running_check.take()?.recv().ok()?;
+        match result {
+            Ok(dependencies) => {
+                self.pks.borrow_mut().set_dependencies(dependencies);
+                None
+            }
+            Err(e) => Some(Action::Error(format!(
+                "Failed to check dependencies: {}",
+                e
+            ))),
+        }
+    }
+
+    fn pack_dependencies(&self) -> Vec<Arc<PackDependencies>> {
+        let pack_grouper = self.pks.borrow_mut().get_pack_grouper();
+        match self.pks.borrow().get_dependencies() {
+            Some(dependencies) => {
+                dependencies.pack_dependencies_for_path(&self.path, &pack_grouper)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Previews removing the selected unused dependency from the pack's `package.yml`
+    fn plan_remove_dependency(&mut self) -> Option<Action> {
+        let pack_dependencies = self.pack_dependencies();
+        let pack = pack_dependencies.get(self.selected_pack)?;
+        let dependency_name = pack.unused_dependencies.get(self.selected_dependency)?;
+        let plan = self
+            .pks
+            .borrow_mut()
+            .plan_remove_dependency(&pack.pack_name, dependency_name);
+        match plan {
+            Ok(edit) => {
+                self.pending_edit = Some(edit);
+                None
+            }
+            Err(e) => Some(Action::Error(format!("Cannot remove dependency: {:?}", e))),
+        }
+    }
+
+    fn render_packs(&mut self, f: &mut Frame, area: Rect, packs: &[Arc<PackDependencies>]) {
+        self.selected_pack = self.selected_pack.min(packs.len().saturating_sub(1));
+        let header = Row::new(vec!["pack", "declared", "unused", "undeclared"])
+            .bold()
+            .height(1);
+        let rows = packs.iter().map(|pack| {
+            Row::new(vec![
+                Cell::from(pack.pack_name.clone()),
+                Cell::from(pack.declared_dependencies.len().to_string()),
+                Cell::from(pack.unused_dependencies.len().to_string())
+                    .style(Style::default().fg(UNUSED_COLOR)),
+                Cell::from(pack.undeclared_dependencies.len().to_string())
+                    .style(Style::default().fg(UNDECLARED_COLOR)),
+            ])
+        });
+        let title = if self.running_check.is_some() {
+            String::from("dependencies checking...")
+        } else if self.pks.borrow().get_dependencies().is_none() {
+            String::from("dependencies (not checked, r to check)")
+        } else {
+            format!("dependencies: {} ({})", self.path, packs.len())
+        };
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(match self.active_panel {
+                    ActivePanel::Packs => Style::default().fg(Color::Yellow),
+                    ActivePanel::UnusedDependencies => Style::default(),
+                })
+                .title(title),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_pack));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_pack(&mut self, f: &mut Frame, area: Rect, pack: Option<&Arc<PackDependencies>>) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let (unused_dependencies, undeclared_dependencies) = match pack {
+            Some(pack) => (
+                pack.unused_dependencies.as_slice(),
+                pack.undeclared_dependencies.as_slice(),
+            ),
+            None => (&[][..], &[][..]),
+        };
+        self.selected_dependency = self
+            .selected_dependency
+            .min(unused_dependencies.len().saturating_sub(1));
+
+        let unused_items: Vec<ListItem> = unused_dependencies
+            .iter()
+            .map(|name| ListItem::new(name.clone()).style(Style::default().fg(UNUSED_COLOR)))
+            .collect();
+        let unused_list = List::new(unused_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(match self.active_panel {
+                        ActivePanel::Packs => Style::default(),
+                        ActivePanel::UnusedDependencies => Style::default().fg(Color::Yellow),
+                    })
+                    .title(format!(
+                        "declared but unused ({}) {}",
+                        unused_dependencies.len(),
+                        match self.active_panel {
+                            ActivePanel::Packs => "tab to select",
+                            ActivePanel::UnusedDependencies => "x to remove",
+                        }
+                    )),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
+        let mut list_state = ListState::default();
+        if self.active_panel == ActivePanel::UnusedDependencies {
+            list_state.select(Some(self.selected_dependency));
+        }
+        f.render_stateful_widget(unused_list, layout[0], &mut list_state);
+
+        let undeclared_items: Vec<ListItem> = undeclared_dependencies
+            .iter()
+            .map(|(name, count)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(name.clone(), Style::default().fg(UNDECLARED_COLOR)),
+                    Span::raw(format!(" ({} dependency violations)", count)),
+                ]))
+            })
+            .collect();
+        let undeclared_list = List::new(undeclared_items).block(
+            Block::default().borders(Borders::ALL).title(format!(
+                "referenced but undeclared ({})",
+                undeclared_dependencies.len()
+            )),
+        );
+        f.render_widget(undeclared_list, layout[1]);
+    }
+}
+
+impl Component for Dependencies {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.menu_item != MenuItem::Dependencies {
+            return Ok(None);
+        }
+        Ok(confirm_edit_key(&self.pending_edit, key))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::DependenciesChecked => return Ok(self.finish_check()),
+            Action::Menu(menu_item) => {
+                self.menu_item = menu_item;
+                if self.menu_item == MenuItem::Dependencies
+                    && self.pks.borrow().get_dependencies().is_none()
+                {
+                    return Ok(self.check_dependencies());
+                }
+            }
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_pack = 0;
+                self.selected_dependency = 0;
+            }
+            _ if self.menu_item != MenuItem::Dependencies => {}
+            _ if self.pending_edit.is_some() => {
+                return Ok(update_pending_edit(
+                    &mut self.pending_edit,
+                    &self.pks,
+                    action,
+                ));
+            }
+            Action::Refresh => return Ok(self.check_dependencies()),
+            Action::Down => match self.active_panel {
+                ActivePanel::Packs => {
+                    self.selected_pack += 1;
+                    self.selected_dependency = 0;
+                }
+                ActivePanel::UnusedDependencies => self.selected_dependency += 1,
+            },
+            Action::Up => match self.active_panel {
+                ActivePanel::Packs => {
+                    self.selected_pack = self.selected_pack.saturating_sub(1);
+                    self.selected_dependency = 0;
+                }
+                ActivePanel::UnusedDependencies => {
+                    self.selected_dependency = self.selected_dependency.saturating_sub(1);
+                }
+            },
+            Action::NextTab => self.active_panel = ActivePanel::UnusedDependencies,
+            Action::Escape => self.active_panel = ActivePanel::Packs,
+            Action::RemoveDependency if self.active_panel == ActivePanel::UnusedDependencies => {
+                return Ok(self.plan_remove_dependency());
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Dependencies {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Dependencies), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+        let packs = self.pack_dependencies();
+        self.render_packs(f, layout[0], &packs);
+        self.render_pack(f, layout[1], packs.get(self.selected_pack));
+        if let Some(edit) = &self.pending_edit {
+            render_package_yml_edit(f, edit);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_enforcement::{EnforcementCount, EnforcementCoverage, ENFORCEMENT_SETTINGS};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_percentage_bar, build_root_level_vertical_layout, build_top_menu};
+
+const PERCENTAGE_BAR_WIDTH: usize = 20;
+const ENFORCED_COLOR: Color = Color::LightGreen;
+const STRICT_COLOR: Color = Color::LightBlue;
+
+/// Dashboard of enforcement settings across the packs beneath the path selected in the pack tree
+pub struct Enforcement {
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    path: String,
+    selected_setting: usize,
+}
+
+impl Enforcement {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            selected_setting: 0,
+        }
+    }
+
+    fn coverage(&self) -> Arc<EnforcementCoverage> {
+        self.pks.borrow_mut().get_enforcement_coverage(&self.path)
+    }
+
+    fn render_coverage_table(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        coverage: &EnforcementCoverage,
+    ) -> Result<()> {
+        let header = Row::new(vec!["setting", "enforced", "strict", "coverage"])
+            .bold()
+            .height(1);
+        let rows = coverage.counts.iter().map(|count| {
+            Row::new(vec![
+                Cell::from(count.setting.key()),
+                Cell::from(format!("{}/{}", count.enforced_count, count.num_packs)),
+                Cell::from(format!("{}/{}", count.strict_count, count.num_packs)),
+                Cell::from(build_coverage_line(count)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Length(24),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Min(PERCENTAGE_BAR_WIDTH as u16 + 6),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!(
+                    "enforcement coverage: {} ({} packs)",
+                    coverage.path, coverage.num_packs
+                )),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_setting));
+        f.render_stateful_widget(table, area, &mut table_state);
+        Ok(())
+    }
+
+    fn render_unenforced_packs(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        coverage: &EnforcementCoverage,
+    ) -> Result<()> {
+        let Some(count) = coverage.counts.get(self.selected_setting) else {
+            return Ok(());
+        };
+        let items: Vec<ListItem> = count
+            .unenforced_pack_names
+            .iter()
+            .map(|pack_name| ListItem::new(pack_name.clone()))
+            .collect();
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+            "packs missing {} ({})",
+            count.setting.key(),
+            count.unenforced_pack_names.len()
+        )));
+        f.render_widget(list, area);
+        Ok(())
+    }
+}
+
+impl Component for Enforcement {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => self.path = path,
+            Action::Down if self.menu_item == MenuItem::Enforcement => {
+                self.selected_setting =
+                    (self.selected_setting + 1).min(ENFORCEMENT_SETTINGS.len() - 1);
+            }
+            Action::Up if self.menu_item == MenuItem::Enforcement => {
+                self.selected_setting = self.selected_setting.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Enforcement {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Enforcement), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(ENFORCEMENT_SETTINGS.len() as u16 + 3),
+                Constraint::Min(3),
+            ])
+            .split(chunks[1]);
+        let coverage = self.coverage();
+        self.render_coverage_table(f, layout[0], &coverage)?;
+        self.render_unenforced_packs(f, layout[1], &coverage)?;
+        Ok(())
+    }
+}
+
+fn build_coverage_line<'a>(count: &EnforcementCount) -> Line<'a> {
+    Line::from(vec![
+        Span::styled(
+            build_percentage_bar(count.enforced_ratio(), PERCENTAGE_BAR_WIDTH),
+            Style::default().fg(ENFORCED_COLOR),
+        ),
+        Span::styled(
+            format!(" {:>3.0}%", count.enforced_ratio() * 100.0),
+            Style::default().fg(ENFORCED_COLOR),
+        ),
+        Span::styled(
+            format!(" ({:.0}% strict)", count.strict_ratio() * 100.0),
+            Style::default().fg(STRICT_COLOR),
+        ),
+    ])
+}
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_flow::FlowDiagram;
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+/// Each referencing domain keeps its color across its flows
+const DOMAIN_COLORS: [Color; 6] = [
+    Color::LightRed,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightCyan,
+    Color::LightBlue,
+    Color::LightMagenta,
+];
+const NAME_WIDTH_PERCENT: u16 = 25;
+const COUNT_WIDTH: u16 = 8;
+
+/// Violations between the domains beneath the path selected in the pack tree, drawn as
+/// flows from the referencing domain to the defining domain with widths by count
+pub struct Flow {
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    path: String,
+    selected_flow: usize,
+}
+
+impl Flow {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            selected_flow: 0,
+        }
+    }
+
+    fn render_flows(&mut self, f: &mut Frame, area: Rect, diagram: &FlowDiagram) {
+        self.selected_flow = self
+            .selected_flow
+            .min(diagram.flows.len().saturating_sub(1));
+        // borders, highlight symbol and column spacing
+        let name_width = area.width * NAME_WIDTH_PERCENT / 100;
+        let bar_width = area
+            .width
+            .saturating_sub(2 + 3 + 4 + 2 * name_width + COUNT_WIDTH)
+            .max(1) as usize;
+        let max_count = diagram.max_flow_count().max(1);
+        let domain_names: Vec<&String> = diagram.domains.keys().collect();
+
+        let rows = diagram.flows.iter().map(|flow| {
+            let color = domain_color(&domain_names, &flow.from);
+            let width = (flow.count * bar_width / max_count).max(1);
+            Row::new(vec![
+                Cell::from(Line::from(self.domain_name(&flow.from)).alignment(Alignment::Right)),
+                Cell::from(format!("{}▶", "█".repeat(width.saturating_sub(1))))
+                    .style(Style::default().fg(color)),
+                Cell::from(self.domain_name(&flow.to)),
+                Cell::from(flow.count.to_string()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(NAME_WIDTH_PERCENT),
+                Constraint::Min(1),
+                Constraint::Percentage(NAME_WIDTH_PERCENT),
+                Constraint::Length(COUNT_WIDTH),
+            ],
+        )
+        .header(
+            Row::new(vec!["referencing", "", "defining", "count"])
+                .bold()
+                .height(1),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!(
+                    "violation flows between {}/* ({} flows)",
+                    self.path,
+                    diagram.flows.len()
+                )),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_flow));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_domain_totals(&self, f: &mut Frame, area: Rect, diagram: &FlowDiagram) {
+        let domain_names: Vec<&String> = diagram.domains.keys().collect();
+        let rows = diagram.domains.iter().map(|(domain, totals)| {
+            Row::new(vec![
+                Cell::from(self.domain_name(domain))
+                    .style(Style::default().fg(domain_color(&domain_names, domain))),
+                Cell::from(totals.out_count.to_string()),
+                Cell::from(totals.in_count.to_string()),
+                Cell::from(totals.within_count.to_string()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["domain", "out", "in", "within"])
+                .bold()
+                .height(1),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("domains ({})", diagram.domains.len())),
+        );
+        f.render_widget(table, area);
+    }
+
+    /// Domain path relative to the selected path, e.g. "payroll" for "./packs/payroll"
+    fn domain_name(&self, domain: &str) -> String {
+        domain
+            .strip_prefix(&format!("{}/", self.path))
+            .unwrap_or(domain)
+            .to_string()
+    }
+}
+
+impl Component for Flow {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_flow = 0;
+            }
+            _ if self.menu_item != MenuItem::Flow => {}
+            Action::Down => self.selected_flow += 1,
+            Action::Up => self.selected_flow = self.selected_flow.saturating_sub(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Flow {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Flow), chunks[0]);
+
+        let diagram = self.pks.borrow_mut().get_flow_diagram(&self.path);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(chunks[1]);
+        self.render_flows(f, layout[0], &diagram);
+        self.render_domain_totals(f, layout[1], &diagram);
+        Ok(())
+    }
+}
+
+fn domain_color(domain_names: &[&String], domain: &str) -> Color {
+    let index = domain_names
+        .iter()
+        .position(|name| name.as_str() == domain)
+        .unwrap_or_default();
+    DOMAIN_COLORS[index % DOMAIN_COLORS.len()]
+}
@@ -0,0 +1,227 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_health::{HealthScores, MAX_HEALTH_SCORE};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::config::Config;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_percentage_bar, build_root_level_vertical_layout, build_top_menu};
+
+const SCORE_BAR_WIDTH: usize = 10;
+const HEALTHY_SCORE: f64 = 75.0;
+const UNHEALTHY_SCORE: f64 = 50.0;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum Leaderboard {
+    #[default]
+    Packs,
+    Subtrees,
+}
+
+/// Packs and subtrees beneath the path selected in the pack tree, ranked by health score.
+/// The score formula comes from the `health` section of the config file.
+pub struct Health {
+    pks: Rc<RefCell<Pks>>,
+    config: Config,
+    menu_item: MenuItem,
+    path: String,
+    leaderboard: Leaderboard,
+    selected_row: usize,
+}
+
+impl Health {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            config: Config::default(),
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            leaderboard: Leaderboard::default(),
+            selected_row: 0,
+        }
+    }
+
+    fn render_pack_leaderboard(&mut self, f: &mut Frame, area: Rect, scores: &HealthScores) {
+        let pack_grouper = self.pks.borrow_mut().get_pack_grouper();
+        let pack_healths = scores.pack_healths_for_path(&self.path, &pack_grouper);
+        self.selected_row = self.selected_row.min(pack_healths.len().saturating_sub(1));
+        let header = Row::new(vec![
+            "#",
+            "pack",
+            "score",
+            "",
+            "out",
+            "in",
+            "dependents",
+            "enforced",
+            "files",
+        ])
+        .bold()
+        .height(1);
+        let rows = pack_healths.iter().enumerate().map(|(index, pack_health)| {
+            Row::new(vec![
+                Cell::from((index + 1).to_string()),
+                Cell::from(pack_health.pack_name.clone()),
+                build_score_cell(pack_health.score),
+                Cell::from(build_percentage_bar(
+                    pack_health.score / MAX_HEALTH_SCORE,
+                    SCORE_BAR_WIDTH,
+                ))
+                .style(Style::default().fg(score_color(pack_health.score))),
+                Cell::from(format!("-{:.1}", pack_health.out_violation_penalty)),
+                Cell::from(format!("-{:.1}", pack_health.in_violation_penalty)),
+                Cell::from(format!(
+                    "-{:.1} ({})",
+                    pack_health.dependents_penalty, pack_health.dependents_count
+                )),
+                Cell::from(format!("+{:.1}", pack_health.enforcement_bonus)),
+                Cell::from(format!(
+                    "-{:.1} ({})",
+                    pack_health.size_penalty, pack_health.file_count
+                )),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Length(4),
+                Constraint::Min(20),
+                Constraint::Length(6),
+                Constraint::Length(SCORE_BAR_WIDTH as u16),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Length(9),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(self.build_block(format!(
+            "pack health: {} ({} packs)",
+            self.path,
+            pack_healths.len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_row));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_subtree_leaderboard(&mut self, f: &mut Frame, area: Rect, scores: &HealthScores) {
+        let path_healths = scores.path_healths_for_path(&self.path);
+        self.selected_row = self.selected_row.min(path_healths.len().saturating_sub(1));
+        let header = Row::new(vec!["#", "path", "mean score", "", "packs"])
+            .bold()
+            .height(1);
+        let rows = path_healths.iter().enumerate().map(|(index, path_health)| {
+            Row::new(vec![
+                Cell::from((index + 1).to_string()),
+                Cell::from(path_health.path.clone()),
+                build_score_cell(path_health.score),
+                Cell::from(build_percentage_bar(
+                    path_health.score / MAX_HEALTH_SCORE,
+                    SCORE_BAR_WIDTH,
+                ))
+                .style(Style::default().fg(score_color(path_health.score))),
+                Cell::from(path_health.num_packs.to_string()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Length(4),
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Length(SCORE_BAR_WIDTH as u16),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header)
+        .block(self.build_block(format!(
+            "subtree health: {} ({} subtrees)",
+            self.path,
+            path_healths.len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_row));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn build_block<'a>(&self, title: String) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(title)
+            .title(block::Title::from("(tab) packs/subtrees").alignment(Alignment::Right))
+    }
+}
+
+impl Component for Health {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_row = 0;
+            }
+            _ if self.menu_item != MenuItem::Health => {}
+            Action::Down => self.selected_row += 1,
+            Action::Up => self.selected_row = self.selected_row.saturating_sub(1),
+            Action::NextTab => {
+                self.leaderboard = match self.leaderboard {
+                    Leaderboard::Packs => Leaderboard::Subtrees,
+                    Leaderboard::Subtrees => Leaderboard::Packs,
+                };
+                self.selected_row = 0;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Health {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Health), chunks[0]);
+
+        let scores: Arc<HealthScores> =
+            self.pks.borrow_mut().get_health_scores(&self.config.health);
+        match self.leaderboard {
+            Leaderboard::Packs => self.render_pack_leaderboard(f, chunks[1], &scores),
+            Leaderboard::Subtrees => self.render_subtree_leaderboard(f, chunks[1], &scores),
+        }
+        Ok(())
+    }
+}
+
+fn score_color(score: f64) -> Color {
+    if score >= HEALTHY_SCORE {
+        Color::LightGreen
+    } else if score >= UNHEALTHY_SCORE {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+fn build_score_cell<'a>(score: f64) -> Cell<'a> {
+    Cell::from(format!("{:.1}", score)).style(
+        Style::default()
+            .fg(score_color(score))
+            .add_modifier(Modifier::BOLD),
+    )
+}
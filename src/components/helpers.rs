@@ -1,4 +1,7 @@
 pub mod active_violations;
+pub mod navigation_history;
+pub mod package_yml_edit;
 pub mod scroll_sortable;
 pub mod stateful_tree;
+pub mod tree_badges;
 pub mod violations_display;
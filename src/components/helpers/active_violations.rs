@@ -1,5 +1,6 @@
 use crate::components::helpers::scroll_sortable::{ScrollSortable, SortDirection};
 use crate::components::helpers::violations_display::VIOLATION_HEADER_ABBR_TITLES;
+use packs_client::pks::PathViolations;
 use serde::{Deserialize, Serialize};
 
 pub const UNCONTAINED_OUT_SORTABLE: usize = 0;
@@ -20,3 +21,15 @@ impl Default for ActiveViolations {
         Self::Out
     }
 }
+
+impl ActiveViolations {
+    /// Rows in the violations table for `path_violations`
+    pub fn row_count(&self, path_violations: &PathViolations) -> usize {
+        match self {
+            Self::Out => path_violations.uncontained_out_violations.len(),
+            Self::In => path_violations.uncontained_in_violations.len(),
+            Self::ContainedOut => path_violations.contained_out_violations.len(),
+            Self::ContainedIn => path_violations.contained_in_violations.len(),
+        }
+    }
+}
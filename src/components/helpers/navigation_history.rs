@@ -0,0 +1,167 @@
+use crate::components::helpers::active_violations::ActiveViolations;
+use crate::components::helpers::scroll_sortable::SortDirection;
+use crate::menu::MenuItem;
+
+/// Older entries are dropped past this many
+const MAX_HISTORY_LEN: usize = 100;
+
+/// Where the app was, restored when navigating back or forward
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationEntry {
+    pub menu_item: MenuItem,
+    /// Path selected in the packs tree
+    pub path: String,
+    pub active_violations: ActiveViolations,
+    pub sort_column: usize,
+    pub sort_direction: SortDirection,
+}
+
+/// Entries navigated away from, so a chain of screens and jumps can be walked back and forth
+#[derive(Debug, Default)]
+pub struct NavigationHistory {
+    back: Vec<NavigationEntry>,
+    forward: Vec<NavigationEntry>,
+}
+
+impl NavigationHistory {
+    /// Records leaving `from` for another entry, forgetting the entries gone back from
+    pub fn visit(&mut self, from: NavigationEntry) {
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+        }
+        if self.back.len() > MAX_HISTORY_LEN {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// The entry to return to from `current`, which is None when nothing is selected and so
+    /// cannot be returned to
+    pub fn back(&mut self, current: Option<NavigationEntry>) -> Option<NavigationEntry> {
+        let entry = self.back.pop()?;
+        self.forward.extend(current);
+        Some(entry)
+    }
+
+    /// The entry gone back from, undoing `back`
+    pub fn forward(&mut self, current: Option<NavigationEntry>) -> Option<NavigationEntry> {
+        let entry = self.forward.pop()?;
+        self.back.extend(current);
+        Some(entry)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn entry(path: &str) -> NavigationEntry {
+        NavigationEntry {
+            menu_item: MenuItem::Home,
+            path: path.to_string(),
+            active_violations: ActiveViolations::default(),
+            sort_column: 0,
+            sort_direction: SortDirection::default(),
+        }
+    }
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut history = NavigationHistory::default();
+        assert!(!history.can_go_back());
+        assert_eq!(history.back(Some(entry("./packs/a"))), None);
+        assert!(!history.can_go_forward());
+
+        history.visit(entry("./packs/a"));
+        history.visit(entry("./packs/b"));
+        assert_eq!(
+            history.back(Some(entry("./packs/c"))),
+            Some(entry("./packs/b"))
+        );
+        assert_eq!(
+            history.back(Some(entry("./packs/b"))),
+            Some(entry("./packs/a"))
+        );
+        assert!(!history.can_go_back());
+
+        assert_eq!(
+            history.forward(Some(entry("./packs/a"))),
+            Some(entry("./packs/b"))
+        );
+        assert_eq!(
+            history.forward(Some(entry("./packs/b"))),
+            Some(entry("./packs/c"))
+        );
+        assert_eq!(history.forward(Some(entry("./packs/c"))), None);
+        assert_eq!(
+            history.back(Some(entry("./packs/c"))),
+            Some(entry("./packs/b"))
+        );
+    }
+
+    #[test]
+    fn test_visit_skips_repeated_entries() {
+        let mut history = NavigationHistory::default();
+        history.visit(entry("./packs/a"));
+        history.visit(entry("./packs/a"));
+        assert_eq!(
+            history.back(Some(entry("./packs/b"))),
+            Some(entry("./packs/a"))
+        );
+        assert!(!history.can_go_back());
+    }
+
+    #[test]
+    fn test_visit_forgets_the_entries_gone_back_from() {
+        let mut history = NavigationHistory::default();
+        history.visit(entry("./packs/a"));
+        history.visit(entry("./packs/b"));
+        history.back(Some(entry("./packs/c")));
+        assert!(history.can_go_forward());
+
+        history.visit(entry("./packs/b"));
+        assert!(!history.can_go_forward());
+        assert_eq!(
+            history.back(Some(entry("./packs/d"))),
+            Some(entry("./packs/b"))
+        );
+        assert_eq!(
+            history.back(Some(entry("./packs/b"))),
+            Some(entry("./packs/a"))
+        );
+    }
+
+    #[test]
+    fn test_visit_drops_the_oldest_entries() {
+        let mut history = NavigationHistory::default();
+        for i in 0..MAX_HISTORY_LEN + 5 {
+            history.visit(entry(&format!("./packs/{}", i)));
+        }
+        let mut back_count = 0;
+        let mut oldest = None;
+        while let Some(entry) = history.back(None) {
+            back_count += 1;
+            oldest = Some(entry);
+        }
+        assert_eq!(back_count, MAX_HISTORY_LEN);
+        assert_eq!(oldest, Some(entry("./packs/5")));
+    }
+
+    #[test]
+    fn test_back_without_a_current_entry() {
+        let mut history = NavigationHistory::default();
+        history.visit(entry("./packs/a"));
+        assert_eq!(history.back(None), Some(entry("./packs/a")));
+        assert!(!history.can_go_forward());
+    }
+}
@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use log::info;
+use packs_client::pks::Pks;
+use packs_client::pks_package_yml::{PackageYmlEdit, PreviewLineKind};
+use ratatui::widgets::block::Title;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::action::Action;
+use crate::tui::Frame;
+use crate::ui::shared::centered_rect;
+
+/// Only the enter key writes a previewed edit, not the other keys bound to `Action::Right`
+pub fn confirm_edit_key(pending_edit: &Option<PackageYmlEdit>, key: KeyEvent) -> Option<Action> {
+    if pending_edit.is_some() && key.code == KeyCode::Enter && key.modifiers == KeyModifiers::NONE {
+        Some(Action::ConfirmEdit)
+    } else {
+        None
+    }
+}
+
+/// Handles the actions of the popup previewing `pending_edit`: writes the edit or drops it
+pub fn update_pending_edit(
+    pending_edit: &mut Option<PackageYmlEdit>,
+    pks: &RefCell<Pks>,
+    action: Action,
+) -> Option<Action> {
+    match action {
+        Action::ConfirmEdit => {
+            let edit = pending_edit.take()?;
+            info!("Writing {}", edit.yml_path.display());
+            match pks.borrow_mut().apply_package_yml_edit(&edit) {
+                Ok(()) => Some(Action::PksReloaded),
+                Err(e) => Some(Action::Error(format!(
+                    "Failed to {}: {:?}",
+                    edit.change.title(),
+                    e
+                ))),
+            }
+        }
+        Action::Escape => {
+            *pending_edit = None;
+            None
+        }
+        _ => None,
+    }
+}
+
+pub fn render_package_yml_edit(f: &mut Frame, edit: &PackageYmlEdit) {
+    let area = centered_rect(f.size(), 60, 60);
+    let mut lines: Vec<Line> = edit
+        .warnings
+        .iter()
+        .map(|warning| {
+            Line::from(Span::styled(
+                format!("⚠ {}", warning),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        })
+        .collect();
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines.extend(edit.preview().into_iter().map(|(kind, line)| match kind {
+        PreviewLineKind::Unchanged => Line::from(format!("  {}", line)),
+        PreviewLineKind::Removed => Line::from(Span::styled(
+            format!("- {}", line),
+            Style::default().fg(Color::Red),
+        )),
+        PreviewLineKind::Added => Line::from(Span::styled(
+            format!("+ {}", line),
+            Style::default().fg(Color::Green),
+        )),
+    }));
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(format!(
+                "{}/package.yml: {}",
+                edit.pack_name,
+                edit.change.title()
+            ))
+            .title(Title::from("(enter) write (esc) cancel").alignment(Alignment::Right)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packs_client::pks_package_yml::PackageYmlChange;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_confirm_edit_key() {
+        let edit = Some(PackageYmlEdit {
+            pack_name: String::from("packs/a"),
+            yml_path: PathBuf::from("packs/a/package.yml"),
+            change: PackageYmlChange::AddDependency(String::from("packs/b")),
+            original_contents: String::from("dependencies: []\n"),
+            updated_contents: String::from("dependencies:\n  - packs/b\n"),
+            warnings: vec![],
+        });
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(
+            confirm_edit_key(&edit, key(KeyCode::Enter)),
+            Some(Action::ConfirmEdit)
+        );
+        assert_eq!(confirm_edit_key(&edit, key(KeyCode::Right)), None);
+        assert_eq!(confirm_edit_key(&edit, key(KeyCode::Char('l'))), None);
+        assert_eq!(confirm_edit_key(&None, key(KeyCode::Enter)), None);
+    }
+}
@@ -8,6 +8,12 @@ pub struct ScrollSortable {
     pub sort_column: usize,
     pub focused_column: usize, // so it can be active without sorting
     pub sort_direction: SortDirection,
+    /// Rows in the table, the vertical scroll stays on one of them
+    pub row_count: usize,
+    /// Rows visible at once, set when the table is rendered
+    pub page_size: usize,
+    /// How far the longest pack name can scroll, set when the table is rendered
+    pub max_horizontal_scroll: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -24,8 +30,13 @@ impl Default for SortDirection {
 
 impl ScrollSortable {
     pub fn next_focus_column(&mut self) {
+        self.next_focus_column_of(VIOLATION_HEADER_ABBR_TITLES.len());
+    }
+
+    /// Moves the focus right, wrapping around after the last of `num_columns`
+    pub fn next_focus_column_of(&mut self, num_columns: usize) {
         self.focused_column += 1;
-        if self.focused_column >= VIOLATION_HEADER_ABBR_TITLES.len() {
+        if self.focused_column >= num_columns {
             self.focused_column = 0
         }
     }
@@ -56,14 +67,54 @@ impl ScrollSortable {
         self.set_vertical_scroll(0);
     }
 
+    /// Keeps the vertical scroll on the last row when the table shrinks
+    pub fn set_row_count(&mut self, row_count: usize) {
+        self.row_count = row_count;
+        self.scroll_down_by(0);
+    }
+
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.page_size = page_size;
+    }
+
     pub fn next_vertical_scroll(&mut self) {
-        self.vertical_scroll += 1;
+        self.scroll_down_by(1);
     }
 
     pub fn previous_vertical_scroll(&mut self) {
-        if self.vertical_scroll > 0 {
-            self.vertical_scroll -= 1;
-        }
+        self.scroll_up_by(1);
+    }
+
+    pub fn next_page(&mut self) {
+        self.scroll_down_by(self.page_size.max(1));
+    }
+
+    pub fn previous_page(&mut self) {
+        self.scroll_up_by(self.page_size.max(1));
+    }
+
+    pub fn next_half_page(&mut self) {
+        self.scroll_down_by((self.page_size / 2).max(1));
+    }
+
+    pub fn previous_half_page(&mut self) {
+        self.scroll_up_by((self.page_size / 2).max(1));
+    }
+
+    pub fn first_row(&mut self) {
+        self.reset_vertical_scroll();
+    }
+
+    pub fn last_row(&mut self) {
+        self.vertical_scroll = self.row_count.saturating_sub(1);
+    }
+
+    fn scroll_down_by(&mut self, rows: usize) {
+        self.vertical_scroll = (self.vertical_scroll + rows).min(self.row_count.saturating_sub(1));
+    }
+
+    fn scroll_up_by(&mut self, rows: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_sub(rows);
     }
 
     pub fn set_horizontal_scroll(&mut self, scroll: usize) {
@@ -78,8 +129,16 @@ impl ScrollSortable {
         self.set_horizontal_scroll(0);
     }
 
+    /// Keeps the horizontal scroll within the pack names when the table narrows or widens
+    pub fn set_max_horizontal_scroll(&mut self, max_horizontal_scroll: usize) {
+        self.max_horizontal_scroll = max_horizontal_scroll;
+        self.horizontal_scroll = self.horizontal_scroll.min(max_horizontal_scroll);
+    }
+
     pub fn next_horizontal_scroll(&mut self) {
-        self.horizontal_scroll += 1;
+        if self.horizontal_scroll < self.max_horizontal_scroll {
+            self.horizontal_scroll += 1;
+        }
     }
 
     pub fn previous_horizontal_scroll(&mut self) {
@@ -96,3 +155,99 @@ impl ScrollSortable {
         self.sort_direction = SortDirection::Descending;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn scroll_sortable(row_count: usize, page_size: usize) -> ScrollSortable {
+        let mut scroll_sortable = ScrollSortable::default();
+        scroll_sortable.set_page_size(page_size);
+        scroll_sortable.set_row_count(row_count);
+        scroll_sortable
+    }
+
+    #[test]
+    fn test_no_rows() {
+        let mut scroll_sortable = scroll_sortable(0, 10);
+        scroll_sortable.next_vertical_scroll();
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+        scroll_sortable.next_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+        scroll_sortable.last_row();
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+        scroll_sortable.previous_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+    }
+
+    #[test]
+    fn test_scroll_clamps_when_rows_shrink() {
+        let mut scroll_sortable = scroll_sortable(10, 4);
+        scroll_sortable.last_row();
+        assert_eq!(scroll_sortable.vertical_scroll(), 9);
+        scroll_sortable.next_vertical_scroll();
+        assert_eq!(scroll_sortable.vertical_scroll(), 9);
+
+        scroll_sortable.set_row_count(4);
+        assert_eq!(scroll_sortable.vertical_scroll(), 3);
+        scroll_sortable.set_row_count(20);
+        assert_eq!(scroll_sortable.vertical_scroll(), 3);
+        scroll_sortable.set_row_count(0);
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+    }
+
+    #[test]
+    fn test_pages() {
+        let mut scroll_sortable = scroll_sortable(10, 4);
+        scroll_sortable.next_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 4);
+        scroll_sortable.next_half_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 6);
+        scroll_sortable.next_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 9);
+        scroll_sortable.previous_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 5);
+        scroll_sortable.previous_half_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 3);
+        scroll_sortable.previous_page();
+        assert_eq!(scroll_sortable.vertical_scroll(), 0);
+    }
+
+    #[test]
+    fn test_pages_of_zero_and_one_rows_move_one_row() {
+        for page_size in [0, 1] {
+            let mut scroll_sortable = scroll_sortable(10, page_size);
+            scroll_sortable.next_page();
+            assert_eq!(scroll_sortable.vertical_scroll(), 1);
+            scroll_sortable.next_half_page();
+            assert_eq!(scroll_sortable.vertical_scroll(), 2);
+            scroll_sortable.previous_half_page();
+            assert_eq!(scroll_sortable.vertical_scroll(), 1);
+            scroll_sortable.previous_page();
+            assert_eq!(scroll_sortable.vertical_scroll(), 0);
+        }
+    }
+
+    #[test]
+    fn test_max_horizontal_scroll() {
+        let mut scroll_sortable = ScrollSortable::default();
+        scroll_sortable.set_max_horizontal_scroll(5);
+        for _ in 0..7 {
+            scroll_sortable.next_horizontal_scroll();
+        }
+        assert_eq!(scroll_sortable.horizontal_scroll(), 5);
+
+        scroll_sortable.set_max_horizontal_scroll(3);
+        assert_eq!(scroll_sortable.horizontal_scroll(), 3);
+        scroll_sortable.set_max_horizontal_scroll(8);
+        assert_eq!(scroll_sortable.horizontal_scroll(), 3);
+
+        scroll_sortable.set_max_horizontal_scroll(0);
+        assert_eq!(scroll_sortable.horizontal_scroll(), 0);
+        scroll_sortable.next_horizontal_scroll();
+        scroll_sortable.previous_horizontal_scroll();
+        assert_eq!(scroll_sortable.horizontal_scroll(), 0);
+    }
+}
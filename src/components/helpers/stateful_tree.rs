@@ -44,7 +44,33 @@ impl<'a> StatefulTree<'a> {
         self.state.key_right();
     }
 
+    /// Opens the parents of `path`, e.g. "./packs/foo", and selects it.
+    /// Does nothing when no item has the path.
+    pub fn select_path(&mut self, path: &str) {
+        let identifier = match find_identifier(&self.items, path) {
+            Some(identifier) => identifier,
+            None => return,
+        };
+        for depth in 1..identifier.len() {
+            self.state.open(identifier[..depth].to_vec());
+        }
+        self.state.select(identifier);
+    }
+
     pub fn toggle(&mut self) {
         self.state.toggle_selected();
     }
 }
+
+/// Identifiers from the root down to the item with `path`
+fn find_identifier(items: &[TreeItem<String>], path: &str) -> Option<Vec<String>> {
+    items.iter().find_map(|item| {
+        if item.identifier() == path {
+            return Some(vec![path.to_string()]);
+        }
+        find_identifier(item.children(), path).map(|mut identifier| {
+            identifier.insert(0, item.identifier().clone());
+            identifier
+        })
+    })
+}
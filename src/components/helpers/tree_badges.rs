@@ -0,0 +1,109 @@
+use packs_client::pks_tree_node::PksTreeNode;
+use ratatui::prelude::*;
+use serde::Deserialize;
+
+const WARNING_COLOR: Color = Color::Yellow;
+const DANGER_COLOR: Color = Color::Red;
+
+/// A count shown next to each node in the packs tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum BadgeCount {
+    UncontainedOut,
+    UncontainedIn,
+    ContainedOut,
+    ContainedIn,
+    Packs,
+}
+
+impl BadgeCount {
+    fn count(&self, node: &PksTreeNode) -> usize {
+        match self {
+            BadgeCount::UncontainedOut => node.out_violation_count,
+            BadgeCount::UncontainedIn => node.in_violation_count,
+            BadgeCount::ContainedOut => node.contained_out_violation_count,
+            BadgeCount::ContainedIn => node.contained_in_violation_count,
+            BadgeCount::Packs => node.pack_count,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            BadgeCount::UncontainedOut => "out",
+            BadgeCount::UncontainedIn => "in",
+            BadgeCount::ContainedOut => "c.out",
+            BadgeCount::ContainedIn => "c.in",
+            BadgeCount::Packs => "packs",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            BadgeCount::UncontainedOut => Color::Rgb(109, 0, 0),
+            BadgeCount::UncontainedIn => Color::Rgb(139, 70, 0),
+            BadgeCount::ContainedOut => Color::LightBlue,
+            BadgeCount::ContainedIn => Color::LightCyan,
+            BadgeCount::Packs => Color::DarkGray,
+        }
+    }
+}
+
+/// Read from the `badges` section of the config file, missing fields use the defaults
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BadgeConfig {
+    /// In the order they are shown
+    pub counts: Vec<BadgeCount>,
+    /// "out 3" instead of "(3)"
+    pub labels: bool,
+    /// Violation counts at or above this are yellow, 0 turns it off
+    pub warning: usize,
+    /// Violation counts at or above this are red, 0 turns it off
+    pub danger: usize,
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            counts: vec![BadgeCount::UncontainedOut, BadgeCount::UncontainedIn],
+            labels: false,
+            warning: 0,
+            danger: 0,
+        }
+    }
+}
+
+impl BadgeConfig {
+    pub fn build_spans<'a>(&self, tree_node: &PksTreeNode) -> Vec<Span<'a>> {
+        self.counts
+            .iter()
+            .map(|badge| {
+                let count = badge.count(tree_node);
+                let text = if self.labels {
+                    format!(" {} {}", badge.label(), count)
+                } else {
+                    format!(" ({})", count)
+                };
+                Span::styled(
+                    text,
+                    Style::new()
+                        .fg(self.color(*badge, count))
+                        .bg(Color::Black)
+                        .add_modifier(Modifier::BOLD),
+                )
+            })
+            .collect()
+    }
+
+    fn color(&self, badge: BadgeCount, count: usize) -> Color {
+        if badge == BadgeCount::Packs {
+            return badge.color();
+        }
+        if self.danger > 0 && count >= self.danger {
+            DANGER_COLOR
+        } else if self.warning > 0 && count >= self.warning {
+            WARNING_COLOR
+        } else {
+            badge.color()
+        }
+    }
+}
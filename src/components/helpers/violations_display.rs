@@ -5,6 +5,11 @@ use crate::tui::Frame;
 use color_eyre::owo_colors::OwoColorize;
 use log::info;
 use packs_client::pks::{PackDependentViolation, PathViolations};
+use packs_client::pks_dependencies::DEPENDENCY_VIOLATION_TYPE;
+use packs_client::pks_layers::ARCHITECTURE_VIOLATION_TYPE;
+use packs_client::pks_move_constant::{
+    FOLDER_VISIBILITY_VIOLATION_TYPE, PRIVACY_VIOLATION_TYPE, VISIBILITY_VIOLATION_TYPE,
+};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::prelude::{Line, Span, Style, Stylize};
 use ratatui::style::{Color, Modifier};
@@ -46,6 +51,14 @@ pub const VIOLATION_HEADER_FULL_TITLES: [&str; 7] = [
     "folder visibility",
     "visibility",
 ];
+/// The violation types counted in the columns after the pack names, as the packs crate names them
+pub const VIOLATION_TYPES: [&str; 5] = [
+    PRIVACY_VIOLATION_TYPE,
+    ARCHITECTURE_VIOLATION_TYPE,
+    DEPENDENCY_VIOLATION_TYPE,
+    FOLDER_VISIBILITY_VIOLATION_TYPE,
+    VISIBILITY_VIOLATION_TYPE,
+];
 pub const MIN_HEIGHT_FOR_CONSTANTS: u16 = 20;
 
 impl<'a> ViolationsDisplay<'a> {
@@ -114,13 +127,20 @@ impl<'a> ViolationsDisplay<'a> {
             .collect();
 
         let header = Row::new(header_cells).bold().height(1);
+        let horizontal_scroll = self.scroll_sortable.horizontal_scroll();
         let rows = violation_specifics.violations.iter().map(|violation| {
             let height = 1;
             let mut cells = vec![];
-            cells.push(Cell::from(violation.defining_pack_name.clone()));
-            cells.push(Cell::from(violation.referencing_pack_name.clone()));
-            for key in VIOLATION_HEADER_FULL_TITLES.iter().skip(2) {
-                let count = violation.count_for_violation_type(key);
+            cells.push(Cell::from(scroll_text(
+                &violation.defining_pack_name,
+                horizontal_scroll,
+            )));
+            cells.push(Cell::from(scroll_text(
+                &violation.referencing_pack_name,
+                horizontal_scroll,
+            )));
+            for violation_type in VIOLATION_TYPES {
+                let count = violation.count_for_violation_type(violation_type);
                 cells.push(Cell::from(count.to_string()));
             }
             Row::new(cells).height(height as u16)
@@ -144,11 +164,16 @@ impl<'a> ViolationsDisplay<'a> {
             .highlight_style(selected_style)
             .highlight_symbol(">> ");
 
-        let mut vertical_scroll = self.scroll_sortable.vertical_scroll();
-        if vertical_scroll >= *rows_len && !&violation_specifics.violations.is_empty() {
-            vertical_scroll = *rows_len - 1;
-            self.scroll_sortable.set_vertical_scroll(vertical_scroll);
-        }
+        // borders and header
+        self.scroll_sortable
+            .set_page_size(area.height.saturating_sub(3) as usize);
+        self.scroll_sortable.set_row_count(*rows_len);
+        self.scroll_sortable
+            .set_max_horizontal_scroll(max_horizontal_scroll(
+                &violation_specifics,
+                cols_width,
+                area.width,
+            ));
         let mut table_state =
             TableState::default().with_selected(Some(self.scroll_sortable.vertical_scroll()));
 
@@ -170,21 +195,24 @@ impl<'a> ViolationsDisplay<'a> {
             &mut vertical_scrollbar_state,
         );
 
-        // let horizontal_scrollbar = Scrollbar::default()
-        //     .orientation(ScrollbarOrientation::HorizontalBottom)
-        //     .begin_symbol(Some("←"))
-        //     .end_symbol(Some("→"));
-        // let mut horizontal_scrollbar_state =
-        //     ScrollbarState::new(cols_width).position(self.active_violations.horizontal_scroll());
-        //
-        // f.render_stateful_widget(
-        //     horizontal_scrollbar,
-        //     area.inner(&Margin {
-        //         vertical: 0,
-        //         horizontal: 1,
-        //     }),
-        //     &mut horizontal_scrollbar_state,
-        // );
+        if self.scroll_sortable.max_horizontal_scroll > 0 {
+            let horizontal_scrollbar = Scrollbar::default()
+                .orientation(ScrollbarOrientation::HorizontalBottom)
+                .begin_symbol(Some("←"))
+                .end_symbol(Some("→"));
+            let mut horizontal_scrollbar_state =
+                ScrollbarState::new(self.scroll_sortable.max_horizontal_scroll + 1)
+                    .position(self.scroll_sortable.horizontal_scroll());
+
+            f.render_stateful_widget(
+                horizontal_scrollbar,
+                area.inner(&Margin {
+                    vertical: 0,
+                    horizontal: 1,
+                }),
+                &mut horizontal_scrollbar_state,
+            );
+        }
 
         if show_constants {
             if let Some(constants_paragraph) =
@@ -196,6 +224,15 @@ impl<'a> ViolationsDisplay<'a> {
         Ok(())
     }
 
+    /// The violation in the selected row, in the current sort order
+    pub fn selected_violation(&mut self) -> Option<Arc<PackDependentViolation>> {
+        let violation_specifics = self.get_violation_specifics();
+        violation_specifics
+            .violations
+            .get(self.scroll_sortable.vertical_scroll())
+            .cloned()
+    }
+
     fn build_constants(
         &self,
         violation_specifics: &ViolationSpecifics,
@@ -270,13 +307,11 @@ impl<'a> ViolationsDisplay<'a> {
                 .violations
                 .sort_by(|a, b| a.referencing_pack_name.cmp(&b.referencing_pack_name)),
             2..=6 => specifics.violations.sort_by(|a, b| {
-                a.count_for_violation_type(
-                    VIOLATION_HEADER_FULL_TITLES[self.scroll_sortable.sort_column()],
-                )
-                .cmp(&b.count_for_violation_type(
-                    VIOLATION_HEADER_FULL_TITLES[self.scroll_sortable.sort_column()],
-                ))
-                .then(a.defining_pack_name.cmp(&b.defining_pack_name))
+                a.count_for_violation_type(VIOLATION_TYPES[self.scroll_sortable.sort_column() - 2])
+                    .cmp(&b.count_for_violation_type(
+                        VIOLATION_TYPES[self.scroll_sortable.sort_column() - 2],
+                    ))
+                    .then(a.defining_pack_name.cmp(&b.defining_pack_name))
             }),
             _ => {}
         }
@@ -314,6 +349,38 @@ impl<'a> ViolationsDisplay<'a> {
     }
 }
 
+/// Pack names scroll left together, the count columns stay put
+fn scroll_text(text: &str, horizontal_scroll: usize) -> String {
+    text.chars().skip(horizontal_scroll).collect()
+}
+
+/// How far the longest pack name overflows its column. The pack name columns share the
+/// table width in proportion to their longest names, see `get_constraint_widths`.
+fn max_horizontal_scroll(
+    violation_specifics: &ViolationSpecifics,
+    cols_width: usize,
+    table_width: u16,
+) -> usize {
+    let overflow = |names: Vec<usize>| {
+        let max_len = names.into_iter().max().unwrap_or(0);
+        let column_width = max_len * table_width as usize / cols_width.max(1);
+        max_len.saturating_sub(column_width)
+    };
+    let violations = &violation_specifics.violations;
+    overflow(
+        violations
+            .iter()
+            .map(|violation| violation.defining_pack_name.len())
+            .collect(),
+    )
+    .max(overflow(
+        violations
+            .iter()
+            .map(|violation| violation.referencing_pack_name.len())
+            .collect(),
+    ))
+}
+
 impl<'a> ViolationSpecifics<'a> {
     fn constant_counts_for_index(&self, index: usize) -> Option<Vec<(String, usize)>> {
         if let Some(violation) = self.violations.get(index) {
@@ -330,3 +397,50 @@ impl<'a> ViolationSpecifics<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn violation_specifics(pack_names: &[(&str, &str)]) -> ViolationSpecifics<'static> {
+        ViolationSpecifics {
+            violations: pack_names
+                .iter()
+                .map(|(defining_pack_name, referencing_pack_name)| {
+                    Arc::new(PackDependentViolation {
+                        defining_pack_name: defining_pack_name.to_string(),
+                        referencing_pack_name: referencing_pack_name.to_string(),
+                        violation_type_counts: HashMap::new(),
+                        constant_counts: HashMap::new(),
+                    })
+                })
+                .collect(),
+            table_title: "",
+            color: Color::White,
+        }
+    }
+
+    #[test]
+    fn test_max_horizontal_scroll() {
+        let specifics = violation_specifics(&[
+            ("packs/payments", "packs/ui"),
+            ("packs/ui", "packs/utilities"),
+        ]);
+        // 14 + 15 characters of pack names, 18 of counts and 5 of cushion
+        let cols_width = 52;
+        assert_eq!(max_horizontal_scroll(&specifics, cols_width, 52), 0);
+        assert_eq!(max_horizontal_scroll(&specifics, cols_width, 80), 0);
+        // payments gets 14 * 26 / 52 = 7 columns, utilities 15 * 26 / 52 = 7
+        assert_eq!(max_horizontal_scroll(&specifics, cols_width, 26), 8);
+        assert_eq!(max_horizontal_scroll(&specifics, cols_width, 0), 15);
+    }
+
+    #[test]
+    fn test_max_horizontal_scroll_without_violations() {
+        let specifics = violation_specifics(&[]);
+        assert_eq!(max_horizontal_scroll(&specifics, 0, 80), 0);
+        assert_eq!(max_horizontal_scroll(&specifics, 23, 0), 0);
+    }
+}
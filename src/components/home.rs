@@ -1,27 +1,39 @@
+use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::{collections::HashMap, time::Duration};
 
 use color_eyre::eyre::Result;
 use color_eyre::owo_colors::OwoColorize;
 use crossterm::event::{KeyCode, KeyEvent};
-use log::info;
 use packs_client::pks::{PackDependentViolation, PathViolations, Pks};
-use packs_client::pks_tree_node::PksTreeNode;
+use packs_client::pks_dependencies::DEPENDENCY_VIOLATION_TYPE;
+use packs_client::pks_grouping::is_group_path;
+use packs_client::pks_package_yml::PackageYmlEdit;
+use packs_client::pks_size::PackSizes;
+use packs_client::pks_tree_config::{TreeConfig, TreeGrouping, TreeSort};
+use packs_client::pks_tree_node::{prepend_dot_to_path, PksTreeNode};
 use ratatui::widgets::block::Title;
 use ratatui::{prelude::*, widgets::*};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
-use tui_tree_widget::{Tree, TreeItem};
+use tui_tree_widget::{Tree, TreeItem, TreeState};
 
 use super::{Component, Frame};
 use crate::components::helpers::active_violations::{
     ActiveViolations, CONTAINED_IN_SORTABLE, CONTAINED_OUT_SORTABLE, UNCONTAINED_IN_SORTABLE,
     UNCONTAINED_OUT_SORTABLE,
 };
+use crate::components::helpers::navigation_history::{NavigationEntry, NavigationHistory};
+use crate::components::helpers::package_yml_edit::{
+    confirm_edit_key, render_package_yml_edit, update_pending_edit,
+};
 use crate::components::helpers::scroll_sortable::ScrollSortable;
 use crate::components::helpers::stateful_tree::StatefulTree;
+use crate::components::helpers::tree_badges::BadgeConfig;
 use crate::components::helpers::violations_display::ViolationsDisplay;
+use crate::components::todo::{STALE_COLOR, UNRECORDED_COLOR};
+use crate::menu::MenuItem;
 use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
 use crate::ui::style::Theme;
 use crate::{
@@ -29,14 +41,23 @@ use crate::{
     config::{Config, KeyBindings},
 };
 
+/// Files, lines and public files next to each tree node
+const SIZE_COLOR: Color = Color::DarkGray;
+
 pub struct Home<'a> {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
-    pks: Pks,
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
     pack_tree: StatefulTree<'a>,
     active_violations: ActiveViolations,
     active_panel: ActivePanel,
     scroll_sortable: ScrollSortable,
+    /// A previewed `package.yml` change waiting to be confirmed or cancelled
+    pending_edit: Option<PackageYmlEdit>,
+    history: NavigationHistory,
+    /// Receives the pack sizes being counted, announced by `Action::PackSizesCounted`
+    counting_sizes: Option<mpsc::Receiver<PackSizes>>,
 }
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ActivePanel {
@@ -64,11 +85,79 @@ impl Component for Home<'_> {
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        let grouped = self.pks.borrow_mut().set_tree_config(config.tree.clone());
+        if let Err(e) = grouped {
+            if let Some(tx) = &self.command_tx {
+                tx.send(Action::Error(format!("Invalid tree grouping: {:?}", e)))?;
+            }
+        }
         self.config = config;
+        self.rebuild_tree_items();
+        self.count_pack_sizes();
         Ok(())
     }
 
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.menu_item != MenuItem::Home {
+            return Ok(None);
+        }
+        Ok(confirm_edit_key(&self.pending_edit, key))
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => {
+                // restoring an entry sets the menu item before the action arrives
+                if menu_item != self.menu_item {
+                    if let Some(entry) = self.navigation_entry() {
+                        self.history.visit(entry);
+                    }
+                }
+                self.menu_item = menu_item;
+                return Ok(None);
+            }
+            Action::NavigateBack => {
+                let entry = self.history.back(self.navigation_entry());
+                return Ok(entry.and_then(|entry| self.restore_navigation_entry(entry)));
+            }
+            Action::NavigateForward => {
+                let entry = self.history.forward(self.navigation_entry());
+                return Ok(entry.and_then(|entry| self.restore_navigation_entry(entry)));
+            }
+            Action::TodoStatusUpdated => {
+                self.rebuild_tree_items();
+                return Ok(None);
+            }
+            Action::PksReloaded => {
+                self.rebuild_tree_items();
+                self.count_pack_sizes();
+                return Ok(None);
+            }
+            Action::PackSizesCounted => {
+                self.finish_counting_sizes();
+                return Ok(None);
+            }
+            Action::SelectPath(path) => {
+                self.active_panel = ActivePanel::Tree;
+                return Ok(self.jump_to_path(&path));
+            }
+            _ => {}
+        }
+        if self.menu_item != MenuItem::Home {
+            return Ok(None);
+        }
+        if self.pending_edit.is_some() {
+            return Ok(update_pending_edit(
+                &mut self.pending_edit,
+                &self.pks,
+                action,
+            ));
+        }
+        let selected_path = self.pack_tree.state.selected();
+        let row_count = self.selected_path_violations().map_or(0, |violations| {
+            self.active_violations.row_count(&violations)
+        });
+        self.scroll_sortable.set_row_count(row_count);
         match action {
             Action::Down => {
                 if self.active_panel == ActivePanel::Tree {
@@ -88,16 +177,42 @@ impl Component for Home<'_> {
                 if self.active_panel == ActivePanel::Tree {
                     self.pack_tree.left();
                 } else {
-                    // self.scroll_sortable.previous_horizontal_scroll();
+                    self.scroll_sortable.previous_horizontal_scroll();
                 }
             }
             Action::Right => {
                 if self.active_panel == ActivePanel::Tree {
                     self.pack_tree.right();
                 } else {
-                    // self.scroll_sortable.next_horizontal_scroll();
+                    self.scroll_sortable.next_horizontal_scroll();
+                }
+            }
+            Action::First => {
+                if self.active_panel == ActivePanel::Tree {
+                    self.pack_tree.first();
+                } else {
+                    self.scroll_sortable.first_row();
+                }
+            }
+            Action::Last => {
+                if self.active_panel == ActivePanel::Tree {
+                    self.pack_tree.last();
+                } else {
+                    self.scroll_sortable.last_row();
                 }
             }
+            Action::PageDown if self.active_panel == ActivePanel::Violations => {
+                self.scroll_sortable.next_page();
+            }
+            Action::PageUp if self.active_panel == ActivePanel::Violations => {
+                self.scroll_sortable.previous_page();
+            }
+            Action::HalfPageDown if self.active_panel == ActivePanel::Violations => {
+                self.scroll_sortable.next_half_page();
+            }
+            Action::HalfPageUp if self.active_panel == ActivePanel::Violations => {
+                self.scroll_sortable.previous_half_page();
+            }
             Action::UncontainedOutViolations => {
                 self.active_violations = ActiveViolations::Out;
             }
@@ -128,14 +243,53 @@ impl Component for Home<'_> {
                 self.scroll_sortable.set_sort_column_to_active_column();
                 self.scroll_sortable.sort_descending();
             }
+            Action::AddDependency if self.active_panel == ActivePanel::Violations => {
+                return Ok(self.plan_add_dependency());
+            }
+            Action::JumpToDefiningPack if self.active_panel == ActivePanel::Violations => {
+                return Ok(self.jump_to_violation_pack(|violation| &violation.defining_pack_name));
+            }
+            Action::JumpToReferencingPack if self.active_panel == ActivePanel::Violations => {
+                return Ok(
+                    self.jump_to_violation_pack(|violation| &violation.referencing_pack_name)
+                );
+            }
+            Action::NextTreeGrouping => {
+                let mut tree_config = self.pks.borrow().get_tree_config().clone();
+                tree_config.grouping = tree_config.grouping.next();
+                return Ok(self.change_tree_config(tree_config));
+            }
+            Action::ToggleCompactTree => {
+                let mut tree_config = self.pks.borrow().get_tree_config().clone();
+                tree_config.compact = !tree_config.compact;
+                return Ok(self.change_tree_config(tree_config));
+            }
+            Action::NextTreeSort => {
+                let mut tree_config = self.pks.borrow().get_tree_config().clone();
+                tree_config.sort = tree_config.sort.next();
+                return Ok(self.change_tree_config(tree_config));
+            }
+            Action::ToggleTreeFilter => {
+                let mut tree_config = self.pks.borrow().get_tree_config().clone();
+                tree_config.filter = !tree_config.filter;
+                return Ok(self.change_tree_config(tree_config));
+            }
             _ => {}
         }
+        if selected_path != self.pack_tree.state.selected() {
+            if let Some(path) = self.pack_tree.state.selected().last() {
+                return Ok(Some(Action::PathSelected(path.clone())));
+            }
+        }
         Ok(None)
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Home {
+            return Ok(());
+        }
         let chunks = build_root_level_vertical_layout(f);
-        let top_menu = build_top_menu();
+        let top_menu = build_top_menu(MenuItem::Home);
         f.render_widget(top_menu, chunks[0]);
 
         let outer_layout = Layout::default()
@@ -150,30 +304,205 @@ impl Component for Home<'_> {
             .split(outer_layout[1]);
         self.render_in_out_violations(f, context_layout[0])?;
         self.render_violation_details(f, context_layout[1])?;
+        if let Some(edit) = &self.pending_edit {
+            render_package_yml_edit(f, edit);
+        }
         Ok(())
     }
 }
 
 impl<'a> Home<'a> {
-    pub fn new(mut pks: Pks) -> Self {
-        let pks_tree_data = pks.get_pks_tree_data();
-        let tree_items = build_tree_items(Rc::clone(&pks_tree_data));
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        let pks_tree_data = pks.borrow_mut().get_pks_tree_data();
+        let tree_items = build_tree_items(Rc::clone(&pks_tree_data), &BadgeConfig::default());
         let pack_tree = StatefulTree::with_items(tree_items);
         let scroll_sortable = ScrollSortable::default();
         Self {
             pks,
             command_tx: None,
             config: Config::default(),
+            menu_item: MenuItem::default(),
             pack_tree,
             active_violations: ActiveViolations::default(),
             active_panel: ActivePanel::default(),
             scroll_sortable,
+            pending_edit: None,
+            counting_sizes: None,
+            history: NavigationHistory::default(),
+        }
+    }
+
+    /// Selects `path` in the tree, remembering where it was in the navigation history.
+    /// Explains why when the tree does not show the path, e.g. when filtered out.
+    fn jump_to_path(&mut self, path: &str) -> Option<Action> {
+        let current = self.navigation_entry();
+        self.pack_tree.select_path(path);
+        if self.selected_path().as_deref() != Some(path) {
+            return Some(Action::Error(self.missing_path_error(path)));
+        }
+        if let Some(current) = current.filter(|current| current.path != path) {
+            self.history.visit(current);
+        }
+        self.scroll_sortable.reset_vertical_scroll();
+        Some(Action::PathSelected(path.to_string()))
+    }
+
+    fn missing_path_error(&self, path: &str) -> String {
+        let tree_config = self.pks.borrow().get_tree_config().clone();
+        let reason = if tree_config.filter {
+            format!(
+                "it may be hidden by the filter of nodes with fewer than {} violation(s)",
+                tree_config.min_violations
+            )
+        } else if tree_config.compact {
+            String::from("it may be merged into a compacted directory")
+        } else if is_group_path(path) {
+            format!("the tree is grouped by {}", tree_config.grouping.title())
+        } else {
+            String::from("no pack or directory has that path")
+        };
+        format!("{} is not in the packs tree, {}", path, reason)
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        self.pack_tree.state.selected().last().cloned()
+    }
+
+    /// Where the app is, None when no path is selected as there would be nothing to restore
+    fn navigation_entry(&self) -> Option<NavigationEntry> {
+        Some(NavigationEntry {
+            menu_item: self.menu_item,
+            path: self.selected_path()?,
+            active_violations: self.active_violations,
+            sort_column: self.scroll_sortable.sort_column(),
+            sort_direction: self.scroll_sortable.sort_direction,
+        })
+    }
+
+    /// Goes back to a screen, selected path, violations tab and sort from the history
+    fn restore_navigation_entry(&mut self, entry: NavigationEntry) -> Option<Action> {
+        if entry.menu_item != self.menu_item {
+            self.menu_item = entry.menu_item;
+            if let Some(tx) = &self.command_tx {
+                tx.send(Action::Menu(entry.menu_item)).ok()?;
+            }
+        }
+        self.active_violations = entry.active_violations;
+        self.scroll_sortable.sort_column = entry.sort_column;
+        self.scroll_sortable.focused_column = entry.sort_column;
+        self.scroll_sortable.sort_direction = entry.sort_direction;
+        self.scroll_sortable.reset_vertical_scroll();
+        self.pack_tree.select_path(&entry.path);
+        Some(Action::PathSelected(entry.path))
+    }
+
+    /// Jumps to a pack of the selected violation, staying in the violations table so a chain
+    /// of violations can be followed
+    fn jump_to_violation_pack(
+        &mut self,
+        pack_name: fn(&PackDependentViolation) -> &String,
+    ) -> Option<Action> {
+        let violations = self.selected_path_violations()?;
+        let violation = ViolationsDisplay::new(
+            &mut self.active_violations,
+            violations,
+            &self.active_panel,
+            &mut self.scroll_sortable,
+        )
+        .selected_violation()?;
+        self.jump_to_path(&prepend_dot_to_path(pack_name(&violation)))
+    }
+
+    /// Previews declaring the defining pack of the selected violation as a dependency of the
+    /// referencing pack
+    fn plan_add_dependency(&mut self) -> Option<Action> {
+        let violations = self.selected_path_violations()?;
+        let violation = ViolationsDisplay::new(
+            &mut self.active_violations,
+            violations,
+            &self.active_panel,
+            &mut self.scroll_sortable,
+        )
+        .selected_violation()?;
+        if violation.count_for_violation_type(DEPENDENCY_VIOLATION_TYPE) == 0 {
+            return Some(Action::Error(format!(
+                "{} has no dependency violations on {}, declaring a dependency would not fix them",
+                violation.referencing_pack_name, violation.defining_pack_name
+            )));
+        }
+        let plan = self.pks.borrow_mut().plan_add_dependency(
+            &violation.referencing_pack_name,
+            &violation.defining_pack_name,
+        );
+        match plan {
+            Ok(edit) => {
+                self.pending_edit = Some(edit);
+                None
+            }
+            Err(e) => Some(Action::Error(format!("Cannot add dependency: {:?}", e))),
+        }
+    }
+
+    /// Rebuilds the packs tree, keeping the selected path when the new tree still has it
+    fn change_tree_config(&mut self, tree_config: TreeConfig) -> Option<Action> {
+        let selected_path = self.pack_tree.state.selected().last().cloned();
+        if let Err(e) = self.pks.borrow_mut().set_tree_config(tree_config) {
+            return Some(Action::Error(format!("Invalid tree grouping: {:?}", e)));
         }
+        self.rebuild_tree_items();
+        self.pack_tree.state = TreeState::default();
+        if let Some(first) = self.pack_tree.items.first() {
+            self.pack_tree.state.open(vec![first.identifier().clone()]);
+        }
+        if let Some(path) = selected_path {
+            self.pack_tree.select_path(&path);
+        }
+        if self.pack_tree.state.selected().is_empty() {
+            self.pack_tree.first();
+        }
+        self.active_panel = ActivePanel::Tree;
+        self.pack_tree
+            .state
+            .selected()
+            .last()
+            .map(|path| Action::PathSelected(path.clone()))
+    }
+
+    /// Counts the sizes shown next to the tree nodes in the background, as it reads every
+    /// included file. A count started before a reload is replaced and its result dropped.
+    fn count_pack_sizes(&mut self) {
+        let tx = match &self.command_tx {
+            Some(tx) => tx.clone(),
+            None => return,
+        };
+        let counter = self.pks.borrow_mut().pack_sizes_counter();
+        let (sizes_tx, sizes_rx) = mpsc::channel();
+        self.counting_sizes = Some(sizes_rx);
+        tokio::task::spawn_blocking(move || {
+            let _ = sizes_tx.send(counter());
+            let _ = tx.send(Action::PackSizesCounted);
+        });
+    }
+
+    fn finish_counting_sizes(&mut self) {
+        let pack_sizes = match self.counting_sizes.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(pack_sizes)) => pack_sizes,
+            // a replaced count finished, the current one is still running
+            _ => return,
+        };
+        self.counting_sizes = None;
+        self.pks.borrow_mut().set_pack_sizes(pack_sizes);
+        self.rebuild_tree_items();
+    }
+
+    fn rebuild_tree_items(&mut self) {
+        let pks_tree_data = self.pks.borrow_mut().get_pks_tree_data();
+        self.pack_tree.items = build_tree_items(pks_tree_data, &self.config.badges);
     }
 
     fn draw_tree(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         let title_block = Block::default()
-            .title(format!("packs ({})", self.pks.get_num_packs()))
+            .title(self.tree_title())
             .borders(Borders::ALL)
             .border_style(match self.active_panel {
                 ActivePanel::Tree => Style::default().fg(Color::Yellow),
@@ -182,7 +511,8 @@ impl<'a> Home<'a> {
             .title(match self.active_panel {
                 ActivePanel::Tree => Title::default(),
                 ActivePanel::Violations => Title::from("(esc)").alignment(Alignment::Right),
-            });
+            })
+            .title(Title::from(self.history_title()).alignment(Alignment::Right));
 
         let items = Tree::new(self.pack_tree.items.clone())
             .expect("all item identifiers are unique")
@@ -214,13 +544,50 @@ impl<'a> Home<'a> {
         Ok(())
     }
 
+    /// Shows which ways the navigation history can go
+    fn history_title(&self) -> String {
+        let mut title = String::new();
+        if self.history.can_go_back() {
+            title.push_str("([) back");
+        }
+        if self.history.can_go_forward() {
+            if !title.is_empty() {
+                title.push(' ');
+            }
+            title.push_str("(]) forward");
+        }
+        title
+    }
+
+    fn tree_title(&self) -> String {
+        let mut pks = self.pks.borrow_mut();
+        let num_packs = pks.get_num_packs();
+        let tree_config = pks.get_tree_config();
+        let mut title = format!("packs ({})", num_packs);
+        if tree_config.grouping != TreeGrouping::Directory {
+            title.push_str(&format!(" by {}", tree_config.grouping.title()));
+        }
+        if tree_config.compact {
+            title.push_str(" compact");
+        }
+        if tree_config.sort != TreeSort::Name {
+            title.push_str(&format!(" sorted by {}", tree_config.sort.title()));
+        }
+        if tree_config.filter {
+            title.push_str(&format!(" ≥{} violations", tree_config.min_violations));
+        }
+        title
+    }
+
     fn selected_path_violations(&mut self) -> Option<Arc<PathViolations>> {
         let selected_paths = self.pack_tree.state.selected();
         if selected_paths.is_empty() {
             return None;
         }
         let show_path = selected_paths.last().unwrap();
-        self.pks.get_path_violations_for_path(show_path)
+        self.pks
+            .borrow_mut()
+            .get_path_violations_for_path(show_path)
     }
 
     fn render_violation_details(&mut self, f: &mut Frame, area: Rect) -> Result<()> {
@@ -316,19 +683,28 @@ impl<'a> Home<'a> {
 }
 
 // https://github.com/EdJoPaTo/tui-rs-tree-widget/blob/main/examples/example.rs
-fn build_tree_items<'a>(pks_tree_data: Rc<Vec<PksTreeNode>>) -> Vec<TreeItem<'a, String>> {
+fn build_tree_items<'a>(
+    pks_tree_data: Rc<Vec<PksTreeNode>>,
+    badges: &BadgeConfig,
+) -> Vec<TreeItem<'a, String>> {
     pks_tree_data
         .iter()
-        .map(map_tree_node_to_tree_item)
+        .map(|tree_node| map_tree_node_to_tree_item(tree_node, badges))
         .collect()
 }
 
-fn map_tree_node_to_tree_item<'a>(tree_node: &PksTreeNode) -> TreeItem<'a, String> {
+fn map_tree_node_to_tree_item<'a>(
+    tree_node: &PksTreeNode,
+    badges: &BadgeConfig,
+) -> TreeItem<'a, String> {
     let tree_items = tree_node
         .children
         .as_ref()
         .map_or_else(Vec::new, |children| {
-            children.iter().map(map_tree_node_to_tree_item).collect()
+            children
+                .iter()
+                .map(|child| map_tree_node_to_tree_item(child, badges))
+                .collect()
         });
 
     let styled_node_name = Span::styled(
@@ -339,25 +715,32 @@ fn map_tree_node_to_tree_item<'a>(tree_node: &PksTreeNode) -> TreeItem<'a, Strin
             .add_modifier(Modifier::BOLD),
     );
 
-    let styled_out_violation_count = Span::styled(
-        format!(" ({})", tree_node.out_violation_count),
-        Style::new()
-            .fg(Color::Rgb(109, 0, 0))
-            .bg(Color::Black)
-            .add_modifier(Modifier::BOLD),
-    );
-    let styled_in_violation_count = Span::styled(
-        format!(" ({})", tree_node.in_violation_count),
-        Style::new()
-            .fg(Color::Rgb(139, 70, 0))
-            .bg(Color::Black)
-            .add_modifier(Modifier::BOLD),
-    );
-    let line = Line::from(vec![
-        styled_node_name,
-        styled_out_violation_count,
-        styled_in_violation_count,
-    ]);
+    let mut spans = vec![styled_node_name];
+    spans.extend(badges.build_spans(tree_node));
+    if tree_node.stale_todo_count > 0 {
+        spans.push(Span::styled(
+            format!(" ✗{}", tree_node.stale_todo_count),
+            Style::new().fg(STALE_COLOR).bg(Color::Black),
+        ));
+    }
+    if tree_node.unrecorded_violation_count > 0 {
+        spans.push(Span::styled(
+            format!(" +{}", tree_node.unrecorded_violation_count),
+            Style::new().fg(UNRECORDED_COLOR).bg(Color::Black),
+        ));
+    }
+    if tree_node.size.file_count > 0 {
+        spans.push(Span::styled(
+            format!(
+                " {}f {}l {}p",
+                tree_node.size.file_count,
+                tree_node.size.line_count,
+                tree_node.size.public_file_count
+            ),
+            Style::new().fg(SIZE_COLOR).bg(Color::Black),
+        ));
+    }
+    let line = Line::from(spans);
 
     let text: Text = Text::from(vec![line]);
     TreeItem::new(tree_node.path.clone(), text, tree_items).expect("Failed to create tree item")
@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_layers::{ArchitectureLayers, LayerViolation};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+const LAYER_COLOR: Color = Color::LightGreen;
+const UPWARD_COLOR: Color = Color::Red;
+const DOWNWARD_COLOR: Color = Color::Yellow;
+
+/// Packs grouped by architecture layer, top to bottom, with the violations between layers
+pub struct Layers {
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    vertical_scroll: usize,
+}
+
+impl Layers {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            menu_item: MenuItem::default(),
+            vertical_scroll: 0,
+        }
+    }
+
+    fn render_layers(&mut self, f: &mut Frame, area: Rect, layers: &ArchitectureLayers) {
+        let lines = build_layer_lines(layers);
+        self.vertical_scroll = self.vertical_scroll.min(lines.len().saturating_sub(1));
+        let title = if layers.layers.is_empty() {
+            String::from("layers (none configured in packwerk.yml)")
+        } else {
+            format!("layers ({})", layers.layers.len())
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(title),
+            )
+            .scroll((self.vertical_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_unlayered_packs(&self, f: &mut Frame, area: Rect, layers: &ArchitectureLayers) {
+        let items: Vec<ListItem> = layers
+            .unlayered_pack_names
+            .iter()
+            .map(|pack_name| ListItem::new(pack_name.clone()))
+            .collect();
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("no layer ({})", layers.unlayered_pack_names.len())),
+        );
+        f.render_widget(list, area);
+    }
+}
+
+impl Component for Layers {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::Down if self.menu_item == MenuItem::Layers => {
+                self.vertical_scroll += 1;
+            }
+            Action::Up if self.menu_item == MenuItem::Layers => {
+                self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Layers {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Layers), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(67), Constraint::Percentage(33)])
+            .split(chunks[1]);
+        let layers = self.pks.borrow_mut().get_architecture_layers();
+        self.render_layers(f, layout[0], &layers);
+        self.render_unlayered_packs(f, layout[1], &layers);
+        Ok(())
+    }
+}
+
+fn build_layer_lines<'a>(layers: &ArchitectureLayers) -> Vec<Line<'a>> {
+    let mut lines = vec![];
+    for layer in layers.layers.iter() {
+        lines.push(Line::from(Span::styled(
+            format!("■ {} ({} packs)", layer.name, layer.pack_names.len()),
+            Style::default()
+                .fg(LAYER_COLOR)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for pack_name in layer.pack_names.iter() {
+            lines.push(Line::from(format!("    {}", pack_name)));
+        }
+        for violation in layers.layer_violations_from(&layer.name) {
+            lines.push(build_layer_violation_line(violation));
+        }
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+fn build_layer_violation_line<'a>(violation: &LayerViolation) -> Line<'a> {
+    let (arrow, color) = if violation.upward {
+        ("↑", UPWARD_COLOR)
+    } else {
+        ("↓", DOWNWARD_COLOR)
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("  {} {} ", arrow, violation.defining_layer),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "{} violations ({} architecture)",
+                violation.count, violation.architecture_count
+            ),
+            Style::default().fg(color),
+        ),
+    ])
+}
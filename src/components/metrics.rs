@@ -0,0 +1,200 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_coupling::{CouplingEdges, PackCoupling};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::components::helpers::scroll_sortable::ScrollSortable;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+const METRICS_HEADER_TITLES: [&str; 8] = ["pack", "Ca", "Ce", "I", "public", "files", "A", "D"];
+const METRICS_HEADER_WIDTHS: [u16; 8] = [0, 6, 6, 7, 8, 7, 7, 7];
+const DISTANCE_COLUMN: usize = 7;
+/// Packs this far from the main sequence are highlighted
+const DISTANCE_WARNING: f64 = 0.7;
+
+/// Afferent and efferent coupling, instability, abstractness and distance from the main
+/// sequence for the packs beneath the path selected in the pack tree
+pub struct Metrics {
+    pks: Rc<RefCell<Pks>>,
+    menu_item: MenuItem,
+    path: String,
+    edges: CouplingEdges,
+    scroll_sortable: ScrollSortable,
+}
+
+impl Metrics {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            pks,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            edges: CouplingEdges::default(),
+            // packs furthest from the main sequence first
+            scroll_sortable: ScrollSortable {
+                sort_column: DISTANCE_COLUMN,
+                focused_column: DISTANCE_COLUMN,
+                ..ScrollSortable::default()
+            },
+        }
+    }
+
+    fn build_header(&self) -> Row<'static> {
+        let header_cells: Vec<Line> = METRICS_HEADER_TITLES
+            .iter()
+            .enumerate()
+            .map(|(index, header_title)| {
+                let mut header_title = header_title.to_string();
+                if index == self.scroll_sortable.sort_column() {
+                    header_title = format!(
+                        "{} {}",
+                        if self.scroll_sortable.is_sort_ascending() {
+                            "▼"
+                        } else {
+                            "▲"
+                        },
+                        header_title
+                    )
+                }
+                let style = if index == self.scroll_sortable.focused_column() {
+                    Style::default()
+                        .bg(Color::Yellow)
+                        .fg(Color::Black)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Line::from(vec![Span::styled(header_title, style)]).alignment(Alignment::Center)
+            })
+            .collect();
+        Row::new(header_cells).bold().height(1)
+    }
+
+    /// Sorts by the sort column, ties broken by pack name
+    fn sort(&self, packs: &mut [&PackCoupling]) {
+        let column = self.scroll_sortable.sort_column();
+        let ascending = self.scroll_sortable.is_sort_ascending();
+        packs.sort_by(|a, b| {
+            let ordering = match column {
+                1 => a.afferent.cmp(&b.afferent),
+                2 => a.efferent.cmp(&b.efferent),
+                3 => a.instability.total_cmp(&b.instability),
+                4 => a.public_file_count.cmp(&b.public_file_count),
+                5 => a.file_count.cmp(&b.file_count),
+                6 => a.abstractness.total_cmp(&b.abstractness),
+                DISTANCE_COLUMN => a.distance.total_cmp(&b.distance),
+                _ => a.pack_name.cmp(&b.pack_name),
+            };
+            let ordering = if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            };
+            ordering.then(a.pack_name.cmp(&b.pack_name))
+        });
+    }
+}
+
+impl Component for Metrics {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => self.menu_item = menu_item,
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.scroll_sortable.reset_vertical_scroll();
+            }
+            _ if self.menu_item != MenuItem::Metrics => {}
+            Action::Down => self.scroll_sortable.next_vertical_scroll(),
+            Action::Up => self.scroll_sortable.previous_vertical_scroll(),
+            Action::NextTab => self
+                .scroll_sortable
+                .next_focus_column_of(METRICS_HEADER_TITLES.len()),
+            Action::SortAscending => {
+                self.scroll_sortable.set_sort_column_to_active_column();
+                self.scroll_sortable.sort_ascending();
+            }
+            Action::SortDescending => {
+                self.scroll_sortable.set_sort_column_to_active_column();
+                self.scroll_sortable.sort_descending();
+            }
+            Action::ToggleEdges => {
+                self.edges = match self.edges {
+                    CouplingEdges::Declared => CouplingEdges::Violations,
+                    CouplingEdges::Violations => CouplingEdges::Declared,
+                };
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Metrics {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Metrics), chunks[0]);
+
+        let metrics = self.pks.borrow_mut().get_coupling_metrics(self.edges);
+        let pack_grouper = self.pks.borrow_mut().get_pack_grouper();
+        let mut packs: Vec<&PackCoupling> = metrics
+            .packs
+            .iter()
+            .filter(|pack| pack_grouper.contains(&self.path, &pack.pack_name))
+            .collect();
+        self.sort(&mut packs);
+        self.scroll_sortable.set_row_count(packs.len());
+
+        let rows = packs.iter().map(|pack| {
+            let distance_style = if pack.distance >= DISTANCE_WARNING {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(pack.pack_name.clone()),
+                Cell::from(pack.afferent.to_string()),
+                Cell::from(pack.efferent.to_string()),
+                Cell::from(format!("{:.2}", pack.instability)),
+                Cell::from(pack.public_file_count.to_string()),
+                Cell::from(pack.file_count.to_string()),
+                Cell::from(format!("{:.2}", pack.abstractness)),
+                Cell::from(format!("{:.2}", pack.distance)).style(distance_style),
+            ])
+        });
+        let widths: Vec<Constraint> = METRICS_HEADER_WIDTHS
+            .iter()
+            .map(|width| match width {
+                0 => Constraint::Min(20),
+                width => Constraint::Length(*width),
+            })
+            .collect();
+        let table = Table::new(rows, widths)
+            .header(self.build_header())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(format!(
+                        "coupling from {}: {} ({} packs)",
+                        self.edges.title(),
+                        self.path,
+                        packs.len()
+                    ))
+                    .title(
+                        block::Title::from("(v) declared/violations").alignment(Alignment::Right),
+                    ),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
+        let mut table_state =
+            TableState::default().with_selected(Some(self.scroll_sortable.vertical_scroll()));
+        f.render_stateful_widget(table, chunks[1], &mut table_state);
+        Ok(())
+    }
+}
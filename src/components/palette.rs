@@ -0,0 +1,401 @@
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use packs_client::pks::Pks;
+use packs_client::pks_tree_node::prepend_dot_to_path;
+use ratatui::{prelude::*, widgets::*};
+use strum::{EnumMessage, IntoEnumIterator};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::config::{key_sequence_to_string, Config};
+use crate::menu::{MenuItem, MENU_ITEMS};
+use crate::mode::Mode;
+use crate::ui::shared::centered_rect;
+
+const KEYS_COLOR: Color = Color::Yellow;
+
+/// An action the palette can run, with its arguments filled in
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    /// e.g. "Menu Flow" or "SelectPath packs/foo"
+    name: String,
+    action: Action,
+    description: &'static str,
+    /// Key sequences bound to the action in `Mode::Home`, e.g. "<[>"
+    keys: Vec<String>,
+}
+
+/// Lists every action with a message, its bound keys and description, shown over the current
+/// screen while in `Mode::Palette`. Typing fuzzy filters the list, enter runs the action.
+pub struct Palette {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    pks: Rc<RefCell<Pks>>,
+    active: bool,
+    query: String,
+    commands: Vec<Command>,
+    /// Indices into `commands` matching the query, best first
+    matches: Vec<usize>,
+    selected_match: usize,
+}
+
+impl Palette {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            config: Config::default(),
+            pks,
+            active: false,
+            query: String::new(),
+            commands: Vec::new(),
+            matches: Vec::new(),
+            selected_match: 0,
+        }
+    }
+
+    /// One command per action, or per argument for actions that take a menu item, a mode or
+    /// a pack
+    fn build_commands(&self) -> Vec<Command> {
+        let pack_names: Vec<String> = self
+            .pks
+            .borrow_mut()
+            .get_packs()
+            .iter()
+            .map(|pack| pack.name.clone())
+            .collect();
+        Action::iter()
+            .filter_map(|action| action.get_message().map(|message| (action, message)))
+            .flat_map(|(action, description)| {
+                let actions: Vec<(String, Action)> = match action {
+                    Action::Menu(_) => MENU_ITEMS
+                        .iter()
+                        .map(|item| (format!("{} {:?}", action, item), Action::Menu(*item)))
+                        .collect(),
+                    Action::Mode(_) => Mode::iter()
+                        .filter(|mode| *mode != Mode::Home && *mode != Mode::Palette)
+                        .map(|mode| (format!("{} {:?}", action, mode), Action::Mode(mode)))
+                        .collect(),
+                    Action::SelectPath(_) => pack_names
+                        .iter()
+                        .map(|pack_name| {
+                            (
+                                format!("{} {}", action, pack_name),
+                                Action::SelectPath(prepend_dot_to_path(pack_name)),
+                            )
+                        })
+                        .collect(),
+                    _ => vec![(action.to_string(), action)],
+                };
+                actions
+                    .into_iter()
+                    .map(move |(name, action)| Command {
+                        keys: self.bound_keys(&action),
+                        name,
+                        action,
+                        description,
+                    })
+                    .collect::<Vec<Command>>()
+            })
+            .collect()
+    }
+
+    fn bound_keys(&self, action: &Action) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .config
+            .keybindings
+            .get(&Mode::Home)
+            .map(|keymap| {
+                keymap
+                    .iter()
+                    .filter(|(_, bound_action)| *bound_action == action)
+                    .map(|(key_events, _)| key_sequence_to_string(key_events))
+                    .collect()
+            })
+            .unwrap_or_default();
+        keys.sort();
+        keys
+    }
+
+    fn filter(&mut self) {
+        let mut scored: Vec<(usize, usize)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| {
+                let text = format!("{} {}", command.description, command.name);
+                fuzzy_score(&self.query, &text).map(|score| (index, score))
+            })
+            .collect();
+        // stable, so equal scores keep the order of the Action variants
+        scored.sort_by_key(|(_, score)| Reverse(*score));
+        self.matches = scored.into_iter().map(|(index, _)| index).collect();
+        self.selected_match = 0;
+    }
+
+    /// Leaves palette mode and sends the selected action. Selecting a path also shows the
+    /// packs tree, like jumping to a bookmark.
+    fn run_command(&mut self) -> Result<()> {
+        let command = match self
+            .matches
+            .get(self.selected_match)
+            .and_then(|index| self.commands.get(*index))
+        {
+            Some(command) => command,
+            None => return Ok(()),
+        };
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Mode(Mode::Home))?;
+            if matches!(command.action, Action::SelectPath(_)) {
+                tx.send(Action::Menu(MenuItem::Home))?;
+            }
+            tx.send(command.action.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for Palette {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.active
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.filter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.filter();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Mode(mode) => {
+                self.active = mode == Mode::Palette;
+                self.query.clear();
+                // packs and key bindings can change between openings
+                if self.active {
+                    self.commands = self.build_commands();
+                    self.filter();
+                }
+            }
+            _ if !self.active => {}
+            Action::PaletteNext => self.selected_match += 1,
+            Action::PalettePrevious => {
+                self.selected_match = self.selected_match.saturating_sub(1);
+            }
+            Action::PaletteRun => self.run_command()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, _area: Rect) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.selected_match = self
+            .selected_match
+            .min(self.matches.len().saturating_sub(1));
+        let area = centered_rect(f.size(), 80, 70);
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let input = Paragraph::new(format!(":{}█", self.query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title("command palette")
+                .title(block::Title::from("(esc) close (enter) run").alignment(Alignment::Right)),
+        );
+        f.render_widget(input, layout[0]);
+
+        let rows = self.matches.iter().map(|index| {
+            let command = &self.commands[*index];
+            Row::new(vec![
+                Cell::from(command.name.clone()),
+                Cell::from(command.keys.join(" ")).style(Style::default().fg(KEYS_COLOR)),
+                Cell::from(command.description),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(35),
+                Constraint::Percentage(15),
+                Constraint::Percentage(50),
+            ],
+        )
+        .header(
+            Row::new(vec!["command", "keys", "description"])
+                .bold()
+                .height(1),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("commands ({})", self.matches.len())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_match));
+        f.render_stateful_widget(table, layout[1], &mut table_state);
+        Ok(())
+    }
+}
+
+/// Scores `text` when the non-space characters of `query` appear in it in order, ignoring
+/// case. Consecutive characters and characters starting a word score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == query_char)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 2;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::config::CONFIG;
+
+    fn palette() -> Palette {
+        let root = PathBuf::from("tests/fixtures/layered_app")
+            .canonicalize()
+            .unwrap();
+        let mut palette = Palette::new(Rc::new(RefCell::new(Pks::new(Some(root)))));
+        palette
+            .register_config_handler(json5::from_str(CONFIG).unwrap())
+            .unwrap();
+        palette
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Show the packs tree"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "Show the packs tree"), None);
+        // characters must appear in order
+        assert_eq!(fuzzy_score("ba", "abc"), None);
+        // each character scores 1, plus 2 when starting a word and 2 when consecutive
+        assert_eq!(fuzzy_score("pt", "packs tree"), Some(1 + 2 + 1 + 2));
+        assert_eq!(fuzzy_score("pa", "packs tree"), Some(1 + 2 + 1 + 2));
+        assert_eq!(fuzzy_score("ae", "packs tree"), Some(1 + 1));
+        assert_eq!(
+            fuzzy_score("P T", "packs tree"),
+            fuzzy_score("pt", "packs tree")
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score_orders_word_starts_first() {
+        let query = "flow";
+        let texts = ["follow a long way", "Show the flow", "nothing"];
+        let mut scored: Vec<(&str, usize)> = texts
+            .iter()
+            .filter_map(|text| fuzzy_score(query, text).map(|score| (*text, score)))
+            .collect();
+        scored.sort_by_key(|(_, score)| Reverse(*score));
+        let ordered: Vec<&str> = scored.iter().map(|(text, _)| *text).collect();
+        assert_eq!(ordered, vec!["Show the flow", "follow a long way"]);
+    }
+
+    #[test]
+    fn test_build_commands() {
+        let commands = palette().build_commands();
+        let names: Vec<&str> = commands
+            .iter()
+            .map(|command| command.name.as_str())
+            .collect();
+
+        for item in MENU_ITEMS {
+            assert!(names.contains(&format!("Menu {:?}", item).as_str()));
+        }
+        assert!(names.contains(&"Mode Search"));
+        assert!(names.contains(&"Mode Bookmarks"));
+        assert!(!names.contains(&"Mode Home"));
+        assert!(!names.contains(&"Mode Palette"));
+
+        let select_path = commands
+            .iter()
+            .find(|command| command.name == "SelectPath packs/ui")
+            .unwrap();
+        assert_eq!(
+            select_path.action,
+            Action::SelectPath(String::from("./packs/ui"))
+        );
+
+        let flow = commands
+            .iter()
+            .find(|command| command.action == Action::Menu(MenuItem::Flow))
+            .unwrap();
+        assert_eq!(flow.keys, vec!["<f>"]);
+    }
+
+    #[test]
+    fn test_run_select_path_shows_the_packs_tree() {
+        let mut palette = palette();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        palette.register_action_handler(tx).unwrap();
+        palette.update(Action::Mode(Mode::Palette)).unwrap();
+        palette.query = String::from("selectpath packs/ui");
+        palette.filter();
+        palette.run_command().unwrap();
+
+        let mut actions = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            actions.push(action);
+        }
+        assert_eq!(
+            actions,
+            vec![
+                Action::Mode(Mode::Home),
+                Action::Menu(MenuItem::Home),
+                Action::SelectPath(String::from("./packs/ui")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_public_api::PublicApi as PackPublicApi;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+const REFERENCED_COLOR: Color = Color::LightGreen;
+const UNREFERENCED_COLOR: Color = Color::DarkGray;
+const PRIVATE_COLOR: Color = Color::Red;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum ActivePanel {
+    #[default]
+    Public,
+    Private,
+}
+
+/// The constants in the public folder of the pack selected in the pack tree, and the
+/// private constants other packs reach into
+pub struct PublicApi {
+    command_tx: Option<UnboundedSender<Action>>,
+    pks: Rc<RefCell<Pks>>,
+    /// Receives the public API being found, announced by `Action::PublicApiFound`
+    finding: Option<mpsc::Receiver<PackPublicApi>>,
+    menu_item: MenuItem,
+    path: String,
+    active_panel: ActivePanel,
+    selected_public: usize,
+    selected_private: usize,
+}
+
+impl PublicApi {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            pks,
+            finding: None,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            active_panel: ActivePanel::default(),
+            selected_public: 0,
+            selected_private: 0,
+        }
+    }
+
+    fn pack_name(&self) -> String {
+        self.path.trim_start_matches("./").to_string()
+    }
+
+    /// Finds the public API of the selected pack in the background, unless it is known
+    fn find_public_api(&mut self) {
+        let pack_name = self.pack_name();
+        if self.pks.borrow().get_public_api(&pack_name).is_some() {
+            return;
+        }
+        let tx = match &self.command_tx {
+            Some(tx) => tx.clone(),
+            None => return,
+        };
+        let finder = match self.pks.borrow_mut().public_api_finder(&pack_name) {
+            Some(finder) => finder,
+            None => return,
+        };
+        let (public_api_tx, public_api_rx) = mpsc::channel();
+        // replaces the search for a previously selected pack, its result is dropped
+        self.finding = Some(public_api_rx);
+        tokio::task::spawn_blocking(move || {
+            let _ = public_api_tx.send(finder());
+            let _ = tx.send(Action::PublicApiFound);
+        });
+    }
+
+    fn finish_finding(&mut self) {
+        let public_api = match self.finding.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(public_api)) => public_api,
+            // a replaced search finished, the current one is still running
+            _ => return,
+        };
+        self.finding = None;
+        self.pks.borrow_mut().set_public_api(public_api);
+    }
+
+    fn border_style(&self, panel: ActivePanel) -> Style {
+        if self.active_panel == panel {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    }
+
+    fn render_public_constants(&mut self, f: &mut Frame, area: Rect, public_api: &PackPublicApi) {
+        self.selected_public = self
+            .selected_public
+            .min(public_api.constants.len().saturating_sub(1));
+        let header = Row::new(vec!["constant", "file", "referenced by"])
+            .bold()
+            .height(1);
+        let rows = public_api.constants.iter().map(|constant| {
+            let (referenced_by, color) = if constant.is_referenced() {
+                (
+                    constant
+                        .referencing_pack_names
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    REFERENCED_COLOR,
+                )
+            } else {
+                (String::from("unreferenced"), UNREFERENCED_COLOR)
+            };
+            Row::new(vec![
+                Cell::from(constant.constant_name.clone()).style(Style::default().fg(color)),
+                Cell::from(constant.file.display().to_string()),
+                Cell::from(referenced_by),
+            ])
+        });
+        let referenced_count = public_api
+            .constants
+            .iter()
+            .filter(|constant| constant.is_referenced())
+            .count();
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(30),
+                Constraint::Percentage(40),
+                Constraint::Percentage(30),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.border_style(ActivePanel::Public))
+                .title(format!(
+                    "public: {} ({} constants, {} referenced)",
+                    public_api.public_folder.display(),
+                    public_api.constants.len(),
+                    referenced_count
+                )),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_public));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_private_constants(&mut self, f: &mut Frame, area: Rect, public_api: &PackPublicApi) {
+        self.selected_private = self
+            .selected_private
+            .min(public_api.private_constants.len().saturating_sub(1));
+        let header = Row::new(vec!["constant", "privacy", "referencing packs"])
+            .bold()
+            .height(1);
+        let rows = public_api.private_constants.iter().map(|constant| {
+            Row::new(vec![
+                Cell::from(constant.constant_name.clone())
+                    .style(Style::default().fg(PRIVATE_COLOR)),
+                Cell::from(constant.privacy_violation_count.to_string()),
+                Cell::from(
+                    constant
+                        .referencing_pack_counts
+                        .iter()
+                        .map(|(pack_name, count)| format!("{} ({})", pack_name, count))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(35),
+                Constraint::Length(8),
+                Constraint::Percentage(55),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.border_style(ActivePanel::Private))
+                .title(format!(
+                    "private constants to promote ({})",
+                    public_api.private_constants.len()
+                )),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_private));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+}
+
+impl Component for PublicApi {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => {
+                self.menu_item = menu_item;
+                if self.menu_item == MenuItem::PublicApi {
+                    self.find_public_api();
+                }
+            }
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_public = 0;
+                self.selected_private = 0;
+                self.finding = None;
+                if self.menu_item == MenuItem::PublicApi {
+                    self.find_public_api();
+                }
+            }
+            Action::PublicApiFound => self.finish_finding(),
+            Action::PksReloaded => {
+                self.finding = None;
+                if self.menu_item == MenuItem::PublicApi {
+                    self.find_public_api();
+                }
+            }
+            _ if self.menu_item != MenuItem::PublicApi => {}
+            Action::Down => match self.active_panel {
+                ActivePanel::Public => self.selected_public += 1,
+                ActivePanel::Private => self.selected_private += 1,
+            },
+            Action::Up => match self.active_panel {
+                ActivePanel::Public => {
+                    self.selected_public = self.selected_public.saturating_sub(1);
+                }
+                ActivePanel::Private => {
+                    self.selected_private = self.selected_private.saturating_sub(1);
+                }
+            },
+            Action::NextTab => self.active_panel = ActivePanel::Private,
+            Action::Escape => self.active_panel = ActivePanel::Public,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::PublicApi {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::PublicApi), chunks[0]);
+
+        let public_api = match self.pks.borrow().get_public_api(&self.pack_name()) {
+            Some(public_api) => public_api,
+            None => {
+                let message = if self.finding.is_some() {
+                    format!("Finding references to the public API of {}...", self.path)
+                } else {
+                    format!(
+                        "{} has no package.yml, select a pack in the packs tree",
+                        self.path
+                    )
+                };
+                f.render_widget(
+                    Paragraph::new(message)
+                        .block(Block::default().borders(Borders::ALL).title("public API")),
+                    chunks[1],
+                );
+                return Ok(());
+            }
+        };
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[1]);
+        self.render_public_constants(f, layout[0], &public_api);
+        self.render_private_constants(f, layout[1], &public_api);
+        Ok(())
+    }
+}
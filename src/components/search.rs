@@ -0,0 +1,216 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use packs_client::pks::Pks;
+use packs_client::pks_search::ConstantReference;
+use packs_client::pks_tree_node::prepend_dot_to_path;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::mode::Mode;
+use crate::ui::shared::centered_rect;
+
+const JUMP_TARGET_COLOR: Color = Color::Yellow;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum JumpTarget {
+    #[default]
+    Defining,
+    Referencing,
+}
+
+/// Searches the violations of every constant in the project, shown over the current screen
+/// while in `Mode::Search`. Enter jumps to the defining or referencing pack in the tree.
+pub struct Search {
+    command_tx: Option<UnboundedSender<Action>>,
+    pks: Rc<RefCell<Pks>>,
+    active: bool,
+    query: String,
+    references: Vec<ConstantReference>,
+    selected_reference: usize,
+    jump_target: JumpTarget,
+}
+
+impl Search {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            pks,
+            active: false,
+            query: String::new(),
+            references: Vec::new(),
+            selected_reference: 0,
+            jump_target: JumpTarget::default(),
+        }
+    }
+
+    fn search(&mut self) {
+        self.references = self.pks.borrow().search_constants(&self.query);
+        self.selected_reference = 0;
+    }
+
+    /// Leaves search mode and selects the pack in the packs tree
+    fn jump_to_pack(&mut self) -> Result<()> {
+        let reference = match self.references.get(self.selected_reference) {
+            Some(reference) => reference,
+            None => return Ok(()),
+        };
+        let pack_name = match self.jump_target {
+            JumpTarget::Defining => &reference.defining_pack_name,
+            JumpTarget::Referencing => &reference.referencing_pack_name,
+        };
+        let path = prepend_dot_to_path(pack_name);
+        if let Some(tx) = &self.command_tx {
+            tx.send(Action::Mode(Mode::Home))?;
+            tx.send(Action::Menu(MenuItem::Home))?;
+            tx.send(Action::SelectPath(path))?;
+        }
+        Ok(())
+    }
+
+    fn pack_cell<'a>(&self, pack_name: &str, target: JumpTarget) -> Cell<'a> {
+        let style = if self.jump_target == target {
+            Style::default().fg(JUMP_TARGET_COLOR)
+        } else {
+            Style::default()
+        };
+        Cell::from(pack_name.to_string()).style(style)
+    }
+}
+
+impl Component for Search {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.active
+            || key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.search();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.search();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Mode(mode) => self.active = mode == Mode::Search,
+            // violations are re-read from disk on reload
+            Action::PksReloaded => self.search(),
+            _ if !self.active => {}
+            Action::SearchNext => self.selected_reference += 1,
+            Action::SearchPrevious => {
+                self.selected_reference = self.selected_reference.saturating_sub(1);
+            }
+            Action::SearchTogglePack => {
+                self.jump_target = match self.jump_target {
+                    JumpTarget::Defining => JumpTarget::Referencing,
+                    JumpTarget::Referencing => JumpTarget::Defining,
+                };
+            }
+            Action::SearchJump => self.jump_to_pack()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, _area: Rect) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.selected_reference = self
+            .selected_reference
+            .min(self.references.len().saturating_sub(1));
+        let area = centered_rect(f.size(), 80, 70);
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let input = Paragraph::new(format!("{}█", self.query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title("search constants")
+                .title(
+                    block::Title::from("(esc) close (enter) jump (tab) defining/referencing")
+                        .alignment(Alignment::Right),
+                ),
+        );
+        f.render_widget(input, layout[0]);
+
+        let header = Row::new(vec![
+            "constant",
+            "defining pack",
+            "referencing pack",
+            "types",
+            "files",
+        ])
+        .bold()
+        .height(1);
+        let rows = self.references.iter().map(|reference| {
+            Row::new(vec![
+                Cell::from(reference.constant_name.clone()),
+                self.pack_cell(&reference.defining_pack_name, JumpTarget::Defining),
+                self.pack_cell(&reference.referencing_pack_name, JumpTarget::Referencing),
+                Cell::from(
+                    reference
+                        .violation_types
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ),
+                Cell::from(
+                    reference
+                        .files
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Percentage(25),
+                Constraint::Percentage(18),
+                Constraint::Percentage(18),
+                Constraint::Percentage(14),
+                Constraint::Percentage(25),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("violations ({})", self.references.len())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_reference));
+        f.render_stateful_widget(table, layout[1], &mut table_state);
+        Ok(())
+    }
+}
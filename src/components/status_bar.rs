@@ -0,0 +1,49 @@
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::action::Action;
+
+/// The last error, shown on the bottom line over the current screen until escape is pressed
+#[derive(Default)]
+pub struct StatusBar {
+    error: Option<String>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for StatusBar {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Error(error) => self.error = Some(error),
+            Action::Escape => self.error = None,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, _area: Rect) -> Result<()> {
+        let error = match &self.error {
+            Some(error) => error,
+            None => return Ok(()),
+        };
+        let area = f.size();
+        if area.height == 0 {
+            return Ok(());
+        }
+        let status_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        f.render_widget(Clear, status_area);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(error.clone(), Style::default().fg(Color::Red)),
+                Span::styled(" (esc)", Style::default().fg(Color::DarkGray)),
+            ])),
+            status_area,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+
+use color_eyre::eyre::Result;
+use packs_client::pks::Pks;
+use packs_client::pks_todo::{PackTodoStatus, TodoEntry, TodoStatus};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::action::Action;
+use crate::menu::MenuItem;
+use crate::ui::shared::{build_root_level_vertical_layout, build_top_menu};
+
+pub const STALE_COLOR: Color = Color::Gray;
+pub const UNRECORDED_COLOR: Color = Color::Magenta;
+
+/// Per pack `package_todo.yml` entries that are stale or missing, for the packs beneath the
+/// path selected in the pack tree
+pub struct Todo {
+    command_tx: Option<UnboundedSender<Action>>,
+    pks: Rc<RefCell<Pks>>,
+    /// Receives the result of the running check, announced by `Action::TodoCheckReceived`
+    running_check: Option<mpsc::Receiver<Result<TodoStatus, String>>>,
+    menu_item: MenuItem,
+    path: String,
+    selected_pack: usize,
+}
+
+impl Todo {
+    pub fn new(pks: Rc<RefCell<Pks>>) -> Self {
+        Self {
+            command_tx: None,
+            pks,
+            running_check: None,
+            menu_item: MenuItem::default(),
+            path: String::from("."),
+            selected_pack: 0,
+        }
+    }
+
+    /// Checks the todos in the background, as it parses every included file
+    fn check_todos(&mut self) -> Option<Action> {
+        if self.running_check.is_some() {
+            return None;
+        }
+        let tx = self.command_tx.clone()?;
+        let root = self.pks.borrow().absolute_root();
+        let (result_tx, result_rx) = mpsc::channel();
+        self.running_check = Some(result_rx);
+        log::info!("Checking package_todo.yml files");
+
+        tokio::task::spawn_blocking(move || {
+            let result = TodoStatus::check_project(&root).map_err(|e| format!("{:?}", e));
+            let _ = result_tx.send(result);
+            let _ = tx.send(Action::TodoCheckReceived);
+        });
+        None
+    }
+
+    fn finish_check(&mut self) -> Option<Action> {
+        let result = self.running_check.take()?.recv().ok()?;
+        match result {
+            Ok(todo_status) => {
+                self.pks.borrow_mut().set_todo_status(todo_status);
+                Some(Action::TodoStatusUpdated)
+            }
+            Err(e) => Some(Action::Error(format!("Failed to check todos: {}", e))),
+        }
+    }
+
+    fn pack_statuses(&self) -> Vec<Arc<PackTodoStatus>> {
+        let pack_grouper = self.pks.borrow_mut().get_pack_grouper();
+        match self.pks.borrow().get_todo_status() {
+            Some(todo_status) => todo_status.pack_statuses_for_path(&self.path, &pack_grouper),
+            None => Vec::new(),
+        }
+    }
+
+    fn render_pack_statuses(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        statuses: &[Arc<PackTodoStatus>],
+    ) {
+        self.selected_pack = self.selected_pack.min(statuses.len().saturating_sub(1));
+        let header = Row::new(vec!["pack", "stale", "unrecorded"])
+            .bold()
+            .height(1);
+        let rows = statuses.iter().map(|status| {
+            Row::new(vec![
+                Cell::from(status.pack_name.clone()),
+                Cell::from(status.stale_entries.len().to_string())
+                    .style(Style::default().fg(STALE_COLOR)),
+                Cell::from(status.unrecorded_entries.len().to_string())
+                    .style(Style::default().fg(UNRECORDED_COLOR)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            vec![
+                Constraint::Min(20),
+                Constraint::Length(6),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(format!(
+                    "package_todo.yml: {} ({}){}",
+                    self.path,
+                    statuses.len(),
+                    if self.running_check.is_some() {
+                        " checking..."
+                    } else {
+                        ""
+                    }
+                )),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+        let mut table_state = TableState::default().with_selected(Some(self.selected_pack));
+        f.render_stateful_widget(table, area, &mut table_state);
+    }
+
+    fn render_entries(&self, f: &mut Frame, area: Rect, status: Option<&Arc<PackTodoStatus>>) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let (stale_entries, unrecorded_entries) = match status {
+            Some(status) => (
+                status.stale_entries.as_slice(),
+                status.unrecorded_entries.as_slice(),
+            ),
+            None => (&[][..], &[][..]),
+        };
+        f.render_widget(
+            build_entries_list("stale (can be deleted)", stale_entries, STALE_COLOR),
+            layout[0],
+        );
+        f.render_widget(
+            build_entries_list("unrecorded", unrecorded_entries, UNRECORDED_COLOR),
+            layout[1],
+        );
+    }
+}
+
+impl Component for Todo {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Menu(menu_item) => {
+                self.menu_item = menu_item;
+                if self.menu_item == MenuItem::Todo && self.pks.borrow().get_todo_status().is_none()
+                {
+                    return Ok(self.check_todos());
+                }
+            }
+            Action::PathSelected(path) => {
+                self.path = path;
+                self.selected_pack = 0;
+            }
+            Action::TodoCheckReceived => return Ok(self.finish_check()),
+            Action::Refresh if self.menu_item == MenuItem::Todo => {
+                return Ok(self.check_todos());
+            }
+            Action::Down if self.menu_item == MenuItem::Todo => {
+                self.selected_pack += 1;
+            }
+            Action::Up if self.menu_item == MenuItem::Todo => {
+                self.selected_pack = self.selected_pack.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.menu_item != MenuItem::Todo {
+            return Ok(());
+        }
+        let chunks = build_root_level_vertical_layout(f);
+        f.render_widget(build_top_menu(MenuItem::Todo), chunks[0]);
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[1]);
+        let statuses = self.pack_statuses();
+        self.render_pack_statuses(f, layout[0], &statuses);
+        self.render_entries(f, layout[1], statuses.get(self.selected_pack));
+        Ok(())
+    }
+}
+
+fn build_entries_list<'a>(title: &str, entries: &[TodoEntry], color: Color) -> List<'a> {
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", entry.violation_type),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(format!(
+                        "{} ({})",
+                        entry.constant_name, entry.defining_pack_name
+                    )),
+                ]),
+                Line::from(Span::styled(
+                    format!("  {}", entry.file),
+                    Style::default().fg(Color::DarkGray),
+                )),
+            ])
+        })
+        .collect();
+    List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "{} ({})",
+        title,
+        entries.len()
+    )))
+}
@@ -1,19 +1,61 @@
-use std::{collections::HashMap, fmt, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use color_eyre::eyre::Result;
 use config::Value;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
+use packs_client::pks_health::HealthWeights;
+use packs_client::pks_tree_config::TreeConfig;
 use ratatui::style::{Color, Modifier, Style};
 use serde::{
     de::{self, Deserializer, MapAccess, Visitor},
     Deserialize, Serialize,
 };
 use serde_json::Value as JsonValue;
+use strum::IntoEnumIterator;
+
+use crate::{action::Action, components::helpers::tree_badges::BadgeConfig, mode::Mode};
+
+/// The default configuration, also the starter file written by `config init`
+pub const CONFIG: &str = include_str!("../.config/config.json5");
+
+/// Config files looked for in the config dir, later ones override earlier ones
+pub const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+    ("config.json5", config::FileFormat::Json5),
+    ("config.json", config::FileFormat::Json),
+    ("config.yaml", config::FileFormat::Yaml),
+    ("config.toml", config::FileFormat::Toml),
+    ("config.ini", config::FileFormat::Ini),
+];
+
+/// Key bindings by mode name and key sequence, before they are parsed
+type RawKeyBindings = HashMap<String, HashMap<String, JsonValue>>;
+/// Styles by mode name and style name, before they are parsed
+type RawStyles = HashMap<String, HashMap<String, String>>;
+
+/// A config entry that could not be used, the default is used in its place
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// The config file, or "defaults" for the embedded one
+    pub source: String,
+    /// The entry, e.g. "keybindings.Home.<Ctrl-x>", empty when the whole file is unusable
+    pub location: String,
+    pub message: String,
+}
 
-use crate::{action::Action, mode::Mode};
-
-const CONFIG: &str = include_str!("../.config/config.json5");
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "{}: {}", self.source, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.source, self.location, self.message)
+        }
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct AppConfig {
@@ -31,47 +73,86 @@ pub struct Config {
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    #[serde(default)]
+    pub health: HealthWeights,
+    #[serde(default)]
+    pub tree: TreeConfig,
+    #[serde(default)]
+    pub badges: BadgeConfig,
+    /// Milliseconds to wait for the next key of a multi-key binding
+    #[serde(default = "default_chord_timeout")]
+    pub chord_timeout: u64,
+    /// Found while loading, reported at startup and by `config check`
+    #[serde(skip)]
+    pub problems: Vec<ConfigProblem>,
+}
+
+fn default_chord_timeout() -> u64 {
+    1000
 }
 
 impl Config {
     pub fn new() -> Result<Self, config::ConfigError> {
-        let default_config: Config = json5::from_str(CONFIG).unwrap();
+        let mut problems = Vec::new();
+        let default_config: Config = json5::from_str(CONFIG).unwrap_or_else(|e| {
+            problems.push(ConfigProblem {
+                source: String::from("defaults"),
+                location: String::new(),
+                message: e.to_string(),
+            });
+            Config::default()
+        });
         let data_dir = crate::utils::get_data_dir();
         let config_dir = crate::utils::get_config_dir();
         let mut builder = config::Config::builder()
             .set_default("_data_dir", data_dir.to_str().unwrap())?
             .set_default("_config_dir", config_dir.to_str().unwrap())?;
 
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-            ("config.ini", config::FileFormat::Ini),
-        ];
         let mut found_config = false;
-        for (file, format) in &config_files {
-            builder = builder.add_source(
-                config::File::from(config_dir.join(file))
-                    .format(*format)
-                    .required(false),
-            );
-            if config_dir.join(file).exists() {
-                found_config = true
+        for (file, format) in &CONFIG_FILES {
+            let path = config_dir.join(file);
+            if !path.exists() {
+                continue;
+            }
+            found_config = true;
+            let file_problems = check_config_file(&path, *format);
+            // an unusable file is left out so the other files and the defaults still apply
+            let usable = file_problems
+                .iter()
+                .all(|problem| !problem.location.is_empty());
+            problems.extend(file_problems);
+            if usable {
+                builder = builder.add_source(config::File::from(path).format(*format));
             }
         }
         if !found_config {
             log::error!("No configuration file found. Application may not behave as expected");
         }
 
-        let mut cfg: Self = builder.build()?.try_deserialize()?;
+        let mut cfg: Self = match builder.build().and_then(|built| built.try_deserialize()) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                problems.push(ConfigProblem {
+                    source: config_dir.display().to_string(),
+                    location: String::new(),
+                    message: e.to_string(),
+                });
+                default_config.clone()
+            }
+        };
 
         for (mode, default_bindings) in default_config.keybindings.iter() {
             let user_bindings = cfg.keybindings.entry(*mode).or_default();
+            let user_keys: Vec<Vec<KeyEvent>> = user_bindings.keys().cloned().collect();
             for (key, cmd) in default_bindings.iter() {
-                user_bindings
-                    .entry(key.clone())
-                    .or_insert_with(|| cmd.clone());
+                // a user binding replaces the defaults it conflicts with, e.g. <g><g> replaces <g>
+                if user_keys
+                    .iter()
+                    .any(|user_key| user_key.starts_with(key) || key.starts_with(user_key))
+                {
+                    continue;
+                }
+                user_bindings.insert(key.clone(), cmd.clone());
             }
         }
         for (mode, default_styles) in default_config.styles.iter() {
@@ -82,11 +163,108 @@ impl Config {
                     .or_insert_with(|| *style);
             }
         }
+        cfg.problems = problems;
 
         Ok(cfg)
     }
 }
 
+/// The config file each user key binding and style in effect comes from, the others are defaults
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSources {
+    pub keybindings: HashMap<Mode, HashMap<Vec<KeyEvent>, String>>,
+    pub styles: HashMap<Mode, HashMap<String, String>>,
+}
+
+impl ConfigSources {
+    /// Follows `Config::new`, where later files override earlier ones and unusable files are
+    /// left out
+    pub fn new(config_dir: &Path) -> Self {
+        let mut sources = Self::default();
+        for (file, format) in &CONFIG_FILES {
+            let path = config_dir.join(file);
+            if !path.exists()
+                || check_config_file(&path, *format)
+                    .iter()
+                    .any(|problem| problem.location.is_empty())
+            {
+                continue;
+            }
+            let file_config = match config::Config::builder()
+                .add_source(config::File::from(path.as_path()).format(*format))
+                .build()
+            {
+                Ok(file_config) => file_config,
+                Err(_) => continue,
+            };
+            let source = path.display().to_string();
+            if let Ok(raw_keybindings) = file_config.get::<RawKeyBindings>("keybindings") {
+                for (mode, bindings) in KeyBindings::from_raw(raw_keybindings).0.iter() {
+                    let mode_sources = sources.keybindings.entry(*mode).or_default();
+                    for keys in bindings.keys() {
+                        mode_sources.insert(keys.clone(), source.clone());
+                    }
+                }
+            }
+            if let Ok(raw_styles) = file_config.get::<RawStyles>("styles") {
+                for (mode, styles) in Styles::from_raw(raw_styles).0.iter() {
+                    let mode_sources = sources.styles.entry(*mode).or_default();
+                    for name in styles.keys() {
+                        mode_sources.insert(name.clone(), source.clone());
+                    }
+                }
+            }
+        }
+        sources
+    }
+}
+
+/// Problems in one config file. A problem without a location means the file is unusable.
+fn check_config_file(path: &Path, format: config::FileFormat) -> Vec<ConfigProblem> {
+    let problem = |location: String, message: String| ConfigProblem {
+        source: path.display().to_string(),
+        location,
+        message,
+    };
+    let file_config = match config::Config::builder()
+        .add_source(config::File::from(path).format(format))
+        .build()
+    {
+        Ok(file_config) => file_config,
+        Err(e) => return vec![problem(String::new(), e.to_string())],
+    };
+    if let Err(e) = file_config.clone().try_deserialize::<Config>() {
+        return vec![problem(String::new(), e.to_string())];
+    }
+    let mut problems = Vec::new();
+    if let Ok(raw_keybindings) = file_config.get::<RawKeyBindings>("keybindings") {
+        let (_, keybinding_problems) = KeyBindings::from_raw(raw_keybindings);
+        problems.extend(keybinding_problems);
+    }
+    if let Ok(raw_styles) = file_config.get::<RawStyles>("styles") {
+        let (_, style_problems) = Styles::from_raw(raw_styles);
+        problems.extend(style_problems);
+    }
+    problems
+        .into_iter()
+        .map(|(location, message)| problem(location, message))
+        .collect()
+}
+
+fn parse_mode(name: &str) -> Result<Mode, String> {
+    serde_json::from_value(JsonValue::String(name.to_string())).map_err(|_| {
+        let modes: Vec<String> = Mode::iter().map(|mode| format!("{:?}", mode)).collect();
+        format!("unknown mode, expected one of {}", modes.join(", "))
+    })
+}
+
+/// Entries of a map in name order, so problems are reported in a stable order
+fn sorted_entries<V>(map: HashMap<String, V>) -> Vec<(String, V)> {
+    let mut entries: Vec<(String, V)> = map.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
 pub struct KeyBindings(pub HashMap<Mode, HashMap<Vec<KeyEvent>, Action>>);
 
@@ -95,23 +273,82 @@ impl<'de> Deserialize<'de> for KeyBindings {
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, Action>>::deserialize(deserializer)?;
-
-        let keybindings = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        // the bindings that cannot be used are reported by `Config::new`
+        let (keybindings, _) = KeyBindings::from_raw(RawKeyBindings::deserialize(deserializer)?);
+        Ok(keybindings)
+    }
+}
 
-        Ok(KeyBindings(keybindings))
+impl KeyBindings {
+    /// Keeps the bindings that can be used, describing the others as (location, message)
+    fn from_raw(raw: RawKeyBindings) -> (Self, Vec<(String, String)>) {
+        let mut problems = Vec::new();
+        let mut keybindings = HashMap::new();
+        for (mode_name, raw_bindings) in sorted_entries(raw) {
+            let mode_location = format!("keybindings.{}", mode_name);
+            let mode = match parse_mode(&mode_name) {
+                Ok(mode) => mode,
+                Err(e) => {
+                    problems.push((mode_location, e));
+                    continue;
+                }
+            };
+            let mut bindings: HashMap<Vec<KeyEvent>, Action> = HashMap::new();
+            let mut key_names: HashMap<Vec<KeyEvent>, String> = HashMap::new();
+            for (key_name, raw_action) in sorted_entries(raw_bindings) {
+                let location = format!("{}.{}", mode_location, key_name);
+                let keys = match parse_key_sequence(&key_name) {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        problems.push((location, e));
+                        continue;
+                    }
+                };
+                let action = match serde_json::from_value::<Action>(raw_action.clone()) {
+                    Ok(action) => action,
+                    Err(_) => {
+                        problems.push((location, format!("unknown action {}", raw_action)));
+                        continue;
+                    }
+                };
+                if let Some(other) = key_names.get(&keys) {
+                    problems.push((location, format!("the same keys as `{}`", other)));
+                    continue;
+                }
+                key_names.insert(keys.clone(), key_name);
+                bindings.insert(keys, action);
+            }
+            // the shorter binding matches first, so the longer one never fires
+            for (prefix, keys) in prefix_conflicts(&bindings) {
+                bindings.remove(&keys);
+                problems.push((
+                    format!("{}.{}", mode_location, key_names[&keys]),
+                    format!("never fires, `{}` is a prefix of it", key_names[&prefix]),
+                ));
+            }
+            keybindings.insert(mode, bindings);
+        }
+        problems.sort();
+        (KeyBindings(keybindings), problems)
     }
 }
 
+/// (prefix, keys) of the bindings whose keys start with the keys of another binding,
+/// e.g. `<g>` and `<g><g>`
+fn prefix_conflicts(
+    keymap: &HashMap<Vec<KeyEvent>, Action>,
+) -> Vec<(Vec<KeyEvent>, Vec<KeyEvent>)> {
+    keymap
+        .keys()
+        .flat_map(|prefix| {
+            keymap
+                .keys()
+                .filter(move |keys| keys.len() > prefix.len() && keys.starts_with(prefix))
+                .map(move |keys| (prefix.clone(), keys.clone()))
+        })
+        .collect()
+}
+
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
     let raw_lower = raw.to_ascii_lowercase();
     let (remaining, modifiers) = extract_modifiers(&raw_lower);
@@ -256,6 +493,14 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
+/// e.g. "<g><g>", the way key sequences are written in the config file
+pub fn key_sequence_to_string(key_events: &[KeyEvent]) -> String {
+    key_events
+        .iter()
+        .map(|key_event| format!("<{}>", key_event_to_string(key_event)))
+        .collect()
+}
+
 pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     if raw.chars().filter(|c| *c == '>').count() != raw.chars().filter(|c| *c == '<').count() {
         return Err(format!("Unable to parse `{}`", raw));
@@ -291,23 +536,55 @@ impl<'de> Deserialize<'de> for Styles {
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, String>>::deserialize(deserializer)?;
-
-        let styles = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        // the styles that cannot be used are reported by `Config::new`
+        let (styles, _) = Styles::from_raw(RawStyles::deserialize(deserializer)?);
+        Ok(styles)
+    }
+}
 
-        Ok(Styles(styles))
+impl Styles {
+    /// Keeps the styles that can be used, describing the others as (location, message)
+    fn from_raw(raw: RawStyles) -> (Self, Vec<(String, String)>) {
+        let mut problems = Vec::new();
+        let mut styles = HashMap::new();
+        for (mode_name, raw_styles) in sorted_entries(raw) {
+            let mode_location = format!("styles.{}", mode_name);
+            let mode = match parse_mode(&mode_name) {
+                Ok(mode) => mode,
+                Err(e) => {
+                    problems.push((mode_location, e));
+                    continue;
+                }
+            };
+            let mut mode_styles = HashMap::new();
+            for (name, style) in sorted_entries(raw_styles) {
+                match check_style(&style) {
+                    Ok(()) => {
+                        mode_styles.insert(name, parse_style(&style));
+                    }
+                    Err(e) => problems.push((format!("{}.{}", mode_location, name), e)),
+                }
+            }
+            styles.insert(mode, mode_styles);
+        }
+        (Styles(styles), problems)
     }
 }
 
+/// `parse_style` ignores colors it does not know, this reports them
+fn check_style(line: &str) -> Result<(), String> {
+    let (foreground, background) =
+        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+    for part in [foreground.to_string(), background.replace("on ", "")] {
+        let (color, _) = process_color_string(&part);
+        let color = color.trim();
+        if !color.is_empty() && parse_color(color).is_none() {
+            return Err(format!("unknown color `{}` in style `{}`", color, line));
+        }
+    }
+    Ok(())
+}
+
 pub fn parse_style(line: &str) -> Style {
     let (foreground, background) =
         line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
@@ -325,6 +602,33 @@ pub fn parse_style(line: &str) -> Style {
     style
 }
 
+/// The inverse of `parse_style`, e.g. "bold color1 on color4"
+pub fn style_to_string(style: &Style) -> String {
+    let mut parts: Vec<String> = [
+        (Modifier::BOLD, "bold"),
+        (Modifier::UNDERLINED, "underline"),
+        (Modifier::REVERSED, "inverse"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+    .map(|(_, name)| name.to_string())
+    .collect();
+    if let Some(fg) = style.fg {
+        parts.push(color_to_string(fg));
+    }
+    if let Some(bg) = style.bg {
+        parts.push(format!("on {}", color_to_string(bg)));
+    }
+    parts.join(" ")
+}
+
+fn color_to_string(color: Color) -> String {
+    match color {
+        Color::Indexed(c) => format!("color{}", c),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
 fn process_color_string(color_str: &str) -> (String, Modifier) {
     let color = color_str
         .replace("grey", "gray")
@@ -417,6 +721,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::menu::MenuItem;
 
     #[test]
     fn test_parse_style_default() {
@@ -443,6 +748,14 @@ mod tests {
         assert_eq!(style.bg, Some(Color::Indexed(4)));
     }
 
+    #[test]
+    fn test_style_to_string() {
+        let style = parse_style("underline red on blue");
+        assert_eq!(style_to_string(&style), "underline color1 on color4");
+        assert_eq!(parse_style(&style_to_string(&style)), style);
+        assert_eq!(style_to_string(&parse_style("bold")), "bold");
+    }
+
     #[test]
     fn test_process_color_string() {
         let (color, modifiers) = process_color_string("underline bold inverse gray");
@@ -465,6 +778,18 @@ mod tests {
         assert_eq!(color, None);
     }
 
+    #[test]
+    fn test_default_health_weights() {
+        let default_config: Config = json5::from_str(CONFIG).unwrap();
+        assert_eq!(default_config.health, HealthWeights::default());
+    }
+
+    #[test]
+    fn test_default_badges() {
+        let default_config: Config = json5::from_str(CONFIG).unwrap();
+        assert_eq!(default_config.badges, BadgeConfig::default());
+    }
+
     #[test]
     fn test_config() -> Result<()> {
         let c = Config::new()?;
@@ -476,9 +801,94 @@ mod tests {
                 .unwrap(),
             &Action::Quit
         );
+        assert_eq!(
+            c.keybindings
+                .get(&Mode::Home)
+                .unwrap()
+                .get(&parse_key_sequence("<e>").unwrap_or_default())
+                .unwrap(),
+            &Action::Menu(MenuItem::Enforcement)
+        );
+        assert_eq!(
+            c.keybindings
+                .get(&Mode::Search)
+                .unwrap()
+                .get(&parse_key_sequence("<esc>").unwrap_or_default())
+                .unwrap(),
+            &Action::Mode(Mode::Home)
+        );
         Ok(())
     }
 
+    #[test]
+    fn test_keybinding_problems() {
+        let raw: RawKeyBindings = json5::from_str(
+            r#"{
+              "Home": {
+                "<g>": "First",
+                "<g><g>": "Last",
+                "<space><e>": "Refresh",
+                "<Ctrl-q>": "Quit",
+                "<ctrl-q>": "Suspend",
+                "<hyper-x>": "Quit",
+                "<x>": "Explode",
+              },
+              "Away": { "<q>": "Quit" },
+            }"#,
+        )
+        .unwrap();
+        let (keybindings, problems) = KeyBindings::from_raw(raw);
+        let problems: Vec<String> = problems
+            .iter()
+            .map(|(location, message)| format!("{}: {}", location, message))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "keybindings.Away: unknown mode, expected one of Home, Search, Bookmarks, Palette",
+                "keybindings.Home.<ctrl-q>: the same keys as `<Ctrl-q>`",
+                "keybindings.Home.<g><g>: never fires, `<g>` is a prefix of it",
+                "keybindings.Home.<hyper-x>: Unable to parse hyper-x",
+                "keybindings.Home.<x>: unknown action \"Explode\"",
+            ]
+        );
+        let home = keybindings.get(&Mode::Home).unwrap();
+        assert_eq!(home.len(), 3);
+        assert_eq!(
+            home.get(&parse_key_sequence("<Ctrl-q>").unwrap()),
+            Some(&Action::Quit)
+        );
+    }
+
+    #[test]
+    fn test_default_config_has_no_problems() {
+        let default_config: JsonValue = json5::from_str(CONFIG).unwrap();
+        let raw: RawKeyBindings =
+            serde_json::from_value(default_config["keybindings"].clone()).unwrap();
+        assert_eq!(KeyBindings::from_raw(raw).1, vec![]);
+        let raw: RawStyles =
+            serde_json::from_value(default_config["styles"].clone()).unwrap_or_default();
+        assert_eq!(Styles::from_raw(raw).1, vec![]);
+    }
+
+    #[test]
+    fn test_check_style() {
+        assert!(check_style("underline red on blue").is_ok());
+        assert!(check_style("bold red").is_ok());
+        assert_eq!(
+            check_style("red on blurple"),
+            Err(String::from(
+                "unknown color `blurple` in style `red on blurple`"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_key_sequence_to_string() {
+        let keys = parse_key_sequence("<space><Ctrl-e>").unwrap();
+        assert_eq!(key_sequence_to_string(&keys), "<space><ctrl-e>");
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
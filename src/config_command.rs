@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result};
+use serde_json::Value as JsonValue;
+use strum::IntoEnumIterator;
+
+use crate::cli::{ConfigCommand, ConfigFormat};
+use crate::config::{
+    key_sequence_to_string, style_to_string, Config, ConfigSources, CONFIG, CONFIG_FILES,
+};
+use crate::mode::Mode;
+use crate::utils::get_config_dir;
+
+/// Source of the entries no config file sets
+const DEFAULTS_SOURCE: &str = "defaults";
+
+const STARTER_HEADER: &str = "\
+// Starter configuration for packs-tui, a copy of the defaults. Change what you need and
+// remove the rest, the entries left out keep their defaults. Run `packs-tui config check`
+// after editing and `packs-tui config show` to see what is in effect.
+";
+
+/// A key binding or style in effect and the file it comes from
+struct Entry {
+    key: String,
+    value: JsonValue,
+    source: String,
+}
+
+/// Entries by section, e.g. "keybindings", then by mode
+type Sections = Vec<(&'static str, Vec<(Mode, Vec<Entry>)>)>;
+
+pub fn run(command: &ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Check => check(),
+        ConfigCommand::Show { format } => show(*format),
+        ConfigCommand::Init { force } => init(&get_config_dir(), *force),
+    }
+}
+
+/// Prints every problem found while loading the configuration, exiting with a failure when
+/// there are any
+fn check() -> Result<()> {
+    let config = Config::new()?;
+    if config.problems.is_empty() {
+        println!("No problems found in {}", get_config_dir().display());
+        return Ok(());
+    }
+    for problem in &config.problems {
+        println!("{problem}");
+    }
+    eprintln!(
+        "{} config problem(s), the defaults are used in their place",
+        config.problems.len()
+    );
+    std::process::exit(libc::EXIT_FAILURE);
+}
+
+fn show(format: ConfigFormat) -> Result<()> {
+    let config = Config::new()?;
+    let sources = ConfigSources::new(&get_config_dir());
+    print!("{}", render(format, &sections(&config, &sources)?));
+    Ok(())
+}
+
+/// The key bindings and styles in effect, sorted by mode then key
+fn sections(config: &Config, sources: &ConfigSources) -> Result<Sections> {
+    let mut keybindings = Vec::new();
+    let mut styles = Vec::new();
+    for mode in Mode::iter() {
+        if let Some(bindings) = config.keybindings.get(&mode) {
+            let mode_sources = sources.keybindings.get(&mode);
+            let mut entries = Vec::new();
+            for (keys, action) in bindings {
+                entries.push(Entry {
+                    key: key_sequence_to_string(keys),
+                    value: serde_json::to_value(action)?,
+                    source: mode_sources
+                        .and_then(|mode_sources| mode_sources.get(keys))
+                        .map_or(DEFAULTS_SOURCE.to_string(), |source| source.clone()),
+                });
+            }
+            entries.sort_by(|a, b| a.key.cmp(&b.key));
+            keybindings.push((mode, entries));
+        }
+        if let Some(mode_styles) = config.styles.get(&mode) {
+            let mode_sources = sources.styles.get(&mode);
+            let mut entries: Vec<Entry> = mode_styles
+                .iter()
+                .map(|(name, style)| Entry {
+                    key: name.clone(),
+                    value: JsonValue::String(style_to_string(style)),
+                    source: mode_sources
+                        .and_then(|mode_sources| mode_sources.get(name))
+                        .map_or(DEFAULTS_SOURCE.to_string(), |source| source.clone()),
+                })
+                .collect();
+            entries.sort_by(|a, b| a.key.cmp(&b.key));
+            styles.push((mode, entries));
+        }
+    }
+    Ok(vec![("keybindings", keybindings), ("styles", styles)])
+}
+
+/// Writes the defaults, which are commented, as a starting point for a config file
+fn init(config_dir: &Path, force: bool) -> Result<()> {
+    let path = config_dir.join(CONFIG_FILES[0].0);
+    if path.exists() && !force {
+        return Err(eyre!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        ));
+    }
+    std::fs::create_dir_all(config_dir)?;
+    std::fs::write(&path, format!("{}{}", STARTER_HEADER, CONFIG))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+/// Each entry is followed by a comment naming its source
+fn render(format: ConfigFormat, sections: &Sections) -> String {
+    let mut out = String::new();
+    match format {
+        ConfigFormat::Json5 => {
+            out.push_str("{\n");
+            for (section, modes) in sections {
+                out.push_str(&format!("  {}: {{\n", quote(section)));
+                for (mode, entries) in modes {
+                    out.push_str(&format!("    \"{:?}\": {{\n", mode));
+                    for entry in entries {
+                        out.push_str(&format!(
+                            "      {}: {}, // {}\n",
+                            quote(&entry.key),
+                            render_value(&entry.value, format),
+                            entry.source
+                        ));
+                    }
+                    out.push_str("    },\n");
+                }
+                out.push_str("  },\n");
+            }
+            out.push_str("}\n");
+        }
+        ConfigFormat::Toml => {
+            for (section, modes) in sections {
+                for (mode, entries) in modes {
+                    out.push_str(&format!("[{}.{:?}]\n", section, mode));
+                    for entry in entries {
+                        out.push_str(&format!(
+                            "{} = {} # {}\n",
+                            quote(&entry.key),
+                            render_value(&entry.value, format),
+                            entry.source
+                        ));
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+        ConfigFormat::Yaml => {
+            for (section, modes) in sections {
+                if modes.is_empty() {
+                    out.push_str(&format!("{}: {{}}\n", section));
+                    continue;
+                }
+                out.push_str(&format!("{}:\n", section));
+                for (mode, entries) in modes {
+                    if entries.is_empty() {
+                        out.push_str(&format!("  {:?}: {{}}\n", mode));
+                        continue;
+                    }
+                    out.push_str(&format!("  {:?}:\n", mode));
+                    for entry in entries {
+                        out.push_str(&format!(
+                            "    {}: {} # {}\n",
+                            quote(&entry.key),
+                            render_value(&entry.value, format),
+                            entry.source
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Strings, numbers and arrays are written the same way in all three formats, only the
+/// inline tables of actions with arguments, e.g. `{ "Menu": "Home" }`, differ
+fn render_value(value: &JsonValue, format: ConfigFormat) -> String {
+    match value {
+        JsonValue::Object(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, value)| match format {
+                    ConfigFormat::Toml => format!("{} = {}", name, render_value(value, format)),
+                    _ => format!("{}: {}", quote(name), render_value(value, format)),
+                })
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        JsonValue::Array(values) => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|value| render_value(value, format))
+                .collect();
+            format!("[{}]", values.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+fn quote(text: &str) -> String {
+    JsonValue::String(text.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use config::FileFormat;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn default_config() -> Config {
+        json5::from_str(CONFIG).unwrap()
+    }
+
+    /// Renders the defaults in `format` and reads them back as a config file would be
+    fn round_trip(format: ConfigFormat) -> Config {
+        let config = default_config();
+        let rendered = render(
+            format,
+            &sections(&config, &ConfigSources::default()).unwrap(),
+        );
+        let file_format = match format {
+            ConfigFormat::Json5 => return json5::from_str(&rendered).unwrap(),
+            ConfigFormat::Toml => FileFormat::Toml,
+            ConfigFormat::Yaml => FileFormat::Yaml,
+        };
+        config::Config::builder()
+            .add_source(config::File::from_str(&rendered, file_format))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rendered_formats_parse_back() {
+        let config = default_config();
+        let home = config.keybindings.get(&Mode::Home).unwrap();
+        // actions with arguments are rendered as inline tables
+        assert!(home
+            .values()
+            .any(|action| *action == crate::action::Action::Mode(Mode::Bookmarks)));
+
+        for format in [ConfigFormat::Json5, ConfigFormat::Toml, ConfigFormat::Yaml] {
+            let parsed = round_trip(format);
+            assert_eq!(*parsed.keybindings, *config.keybindings, "{:?}", format);
+            assert_eq!(*parsed.styles, *config.styles, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_init_does_not_overwrite() {
+        let config_dir =
+            std::env::temp_dir().join(format!("packs-tui-config-init-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&config_dir);
+        let path = config_dir.join(CONFIG_FILES[0].0);
+
+        init(&config_dir, false).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with(STARTER_HEADER));
+
+        std::fs::write(&path, "{}").unwrap();
+        let error = init(&config_dir, false).unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+
+        init(&config_dir, true).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with(STARTER_HEADER));
+
+        std::fs::remove_dir_all(&config_dir).unwrap();
+    }
+}
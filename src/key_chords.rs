@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyEvent;
+
+use crate::action::Action;
+use crate::config::key_sequence_to_string;
+
+/// Turns key presses into actions, waiting for the rest of a multi-key binding like `<g><g>`.
+/// A chord is dropped when its next key does not come within the timeout.
+#[derive(Debug)]
+pub struct KeyChords {
+    timeout: Duration,
+    pending: Vec<KeyEvent>,
+    last_key_at: Option<Instant>,
+}
+
+impl KeyChords {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            pending: Vec::new(),
+            last_key_at: None,
+        }
+    }
+
+    /// The action bound to the keys pressed so far, or None while a chord is pending or when
+    /// nothing is bound. A key that breaks a chord is tried as the start of a new one.
+    pub fn handle_key(
+        &mut self,
+        keymap: &HashMap<Vec<KeyEvent>, Action>,
+        key: KeyEvent,
+        now: Instant,
+    ) -> Option<Action> {
+        self.expire(now);
+        self.pending.push(key);
+        self.last_key_at = Some(now);
+        if let Some(action) = keymap.get(&self.pending) {
+            self.pending.clear();
+            return Some(action.clone());
+        }
+        if keymap.keys().any(|keys| keys.starts_with(&self.pending)) {
+            return None;
+        }
+        let broke_chord = self.pending.len() > 1;
+        self.pending.clear();
+        if broke_chord {
+            return self.handle_key(keymap, key, now);
+        }
+        None
+    }
+
+    /// Drops the pending chord once the timeout has passed since its last key
+    pub fn expire(&mut self, now: Instant) {
+        let expired = self
+            .last_key_at
+            .is_some_and(|last_key_at| now.duration_since(last_key_at) >= self.timeout);
+        if expired {
+            self.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.last_key_at = None;
+    }
+
+    /// The keys of the pending chord, e.g. "<g>", empty when there is none
+    pub fn pending(&self) -> String {
+        key_sequence_to_string(&self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::parse_key_sequence;
+    use crate::menu::MenuItem;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn keymap() -> HashMap<Vec<KeyEvent>, Action> {
+        [
+            ("<q>", Action::Quit),
+            ("<g><g>", Action::First),
+            ("<space><e>", Action::Menu(MenuItem::Enforcement)),
+            ("<space><t><t>", Action::Menu(MenuItem::Todo)),
+        ]
+        .into_iter()
+        .map(|(keys, action)| (parse_key_sequence(keys).unwrap(), action))
+        .collect()
+    }
+
+    /// Presses each key of `keys` `interval` after the previous one
+    fn press(
+        chords: &mut KeyChords,
+        keys: &str,
+        start: Instant,
+        interval: Duration,
+    ) -> Vec<Action> {
+        let keymap = keymap();
+        parse_key_sequence(keys)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, key)| chords.handle_key(&keymap, key, start + interval * i as u32))
+            .collect()
+    }
+
+    #[test]
+    fn test_single_key() {
+        let mut chords = KeyChords::new(TIMEOUT);
+        let actions = press(&mut chords, "<q>", Instant::now(), Duration::ZERO);
+        assert_eq!(actions, vec![Action::Quit]);
+        assert_eq!(chords.pending(), "");
+    }
+
+    #[test]
+    fn test_chords() {
+        let mut chords = KeyChords::new(TIMEOUT);
+        let start = Instant::now();
+        let actions = press(&mut chords, "<g><g><space><e>", start, TIMEOUT / 2);
+        assert_eq!(
+            actions,
+            vec![Action::First, Action::Menu(MenuItem::Enforcement)]
+        );
+
+        let actions = press(&mut chords, "<space><t>", start, TIMEOUT / 2);
+        assert_eq!(actions, vec![]);
+        assert_eq!(chords.pending(), "<space><t>");
+    }
+
+    #[test]
+    fn test_chord_timeout() {
+        let mut chords = KeyChords::new(TIMEOUT);
+        let start = Instant::now();
+        let actions = press(&mut chords, "<g><g>", start, TIMEOUT);
+        assert_eq!(actions, vec![]);
+        assert_eq!(chords.pending(), "<g>");
+
+        chords.expire(start + TIMEOUT * 3);
+        assert_eq!(chords.pending(), "");
+    }
+
+    #[test]
+    fn test_broken_chord_starts_over() {
+        let mut chords = KeyChords::new(TIMEOUT);
+        let actions = press(
+            &mut chords,
+            "<g><q><space><x><g><g>",
+            Instant::now(),
+            Duration::ZERO,
+        );
+        assert_eq!(actions, vec![Action::Quit, Action::First]);
+    }
+}
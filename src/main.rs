@@ -7,14 +7,16 @@ pub mod app;
 pub mod cli;
 pub mod components;
 pub mod config;
-mod menu;
+pub mod config_command;
+pub mod key_chords;
+pub mod menu;
 pub mod mode;
 pub mod tui;
 pub mod ui;
 pub mod utils;
 
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::eyre::Result;
 
 use crate::{
@@ -28,6 +30,9 @@ async fn tokio_main() -> Result<()> {
     initialize_panic_handler()?;
 
     let args = Cli::parse();
+    if let Some(Command::Config { command }) = &args.command {
+        return config_command::run(command);
+    }
     let mut app = App::new(args.tick_rate, args.frame_rate)?;
     app.run().await?;
 
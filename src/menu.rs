@@ -1,16 +1,60 @@
-#[derive(Copy, Clone, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MenuItem {
+    #[default]
     Home,
     Constants,
     Actions,
+    Enforcement,
+    Layers,
+    Todo,
+    Dependencies,
+    Health,
+    Metrics,
+    PublicApi,
+    Flow,
+}
+
+/// Menu items that have a screen behind them, in the order they appear in the top menu
+pub const MENU_ITEMS: [MenuItem; 11] = [
+    MenuItem::Home,
+    MenuItem::Constants,
+    MenuItem::Actions,
+    MenuItem::Enforcement,
+    MenuItem::Layers,
+    MenuItem::Todo,
+    MenuItem::Dependencies,
+    MenuItem::Health,
+    MenuItem::Metrics,
+    MenuItem::PublicApi,
+    MenuItem::Flow,
+];
+
+impl MenuItem {
+    /// The (start, underlined hot key, rest) parts of the menu title
+    pub fn title(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            MenuItem::Home => ("", "P", "acks"),
+            MenuItem::Constants => ("", "C", "onstants"),
+            MenuItem::Actions => ("", "A", "ctions"),
+            MenuItem::Enforcement => ("", "E", "nforcement"),
+            MenuItem::Layers => ("", "L", "ayers"),
+            MenuItem::Todo => ("", "T", "odo"),
+            MenuItem::Dependencies => ("", "D", "ependencies"),
+            MenuItem::Health => ("", "H", "ealth"),
+            MenuItem::Metrics => ("", "M", "etrics"),
+            MenuItem::PublicApi => ("P", "u", "blic API"),
+            MenuItem::Flow => ("", "F", "low"),
+        }
+    }
 }
 
 impl From<MenuItem> for usize {
     fn from(input: MenuItem) -> usize {
-        match input {
-            MenuItem::Home => 0,
-            MenuItem::Constants => 1,
-            MenuItem::Actions => 2,
-        }
+        MENU_ITEMS
+            .iter()
+            .position(|item| *item == input)
+            .unwrap_or_default()
     }
 }
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
 pub enum Mode {
     #[default]
     Home,
+    /// Typing goes to the constant search instead of the key bindings of `Home`
+    Search,
+    /// Typing goes to the bookmarks popup, filtering or naming bookmarks
+    Bookmarks,
+    /// Typing filters the command palette
+    Palette,
 }
@@ -1,6 +1,8 @@
 use ratatui::{prelude::*, widgets::*};
 use std::rc::Rc;
 
+use crate::menu::{MenuItem, MENU_ITEMS};
+
 pub fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -29,15 +31,15 @@ pub fn build_root_level_vertical_layout(frame: &mut Frame) -> Rc<[Rect]> {
     chunks
 }
 
-pub fn build_top_menu<'a>() -> Tabs<'a> {
-    // let menu_titles = [("P", "acks"), ("C", "onstants"), ("A", "ctions")];
-    let menu_titles = [("P", "acks")];
-    let menu = menu_titles
-        .into_iter()
-        .map(|(first, rest)| {
+pub fn build_top_menu<'a>(selected: MenuItem) -> Tabs<'a> {
+    let menu = MENU_ITEMS
+        .iter()
+        .map(|item| {
+            let (start, hot_key, rest) = item.title();
             Line::from(vec![
+                Span::styled(start, Style::default().fg(Color::White)),
                 Span::styled(
-                    first,
+                    hot_key,
                     Style::default()
                         .fg(Color::Gray)
                         .add_modifier(Modifier::UNDERLINED),
@@ -47,10 +49,30 @@ pub fn build_top_menu<'a>() -> Tabs<'a> {
         })
         .collect();
     let tabs = Tabs::new(menu)
-        .select(0)
+        .select(selected.into())
         .block(Block::default().borders(Borders::BOTTOM))
         .style(Style::default().fg(Color::White))
         .highlight_style(Style::default().fg(Color::Yellow))
         .divider(Span::raw("|"));
     tabs
 }
+
+/// Renders `ratio` (0.0..=1.0) as a text bar of `width` cells, e.g. "██████░░░░"
+pub fn build_percentage_bar(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Shows the keys of a multi-key binding waiting for its next key, at the right of the top menu
+pub fn render_pending_chord(frame: &mut Frame, pending: &str) {
+    if pending.is_empty() {
+        return;
+    }
+    let area = frame.size();
+    let width = (pending.chars().count() as u16 + 1).min(area.width);
+    let chord_area = Rect::new(area.right() - width, area.y, width, 1.min(area.height));
+    frame.render_widget(
+        Paragraph::new(format!("{}…", pending)).style(Style::default().fg(Color::Yellow)),
+        chord_area,
+    );
+}
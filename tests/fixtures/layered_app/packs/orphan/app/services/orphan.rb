@@ -0,0 +1,5 @@
+module Orphan
+  def self.pay
+    Payments
+  end
+end
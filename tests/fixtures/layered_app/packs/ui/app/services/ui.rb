@@ -0,0 +1,11 @@
+module Ui
+  class Formatter
+    def format
+      Utilities.call
+    end
+
+    def format_with_api
+      Utilities::Api.call
+    end
+  end
+end
@@ -0,0 +1,5 @@
+module Utilities
+  class Api
+    def self.call; end
+  end
+end
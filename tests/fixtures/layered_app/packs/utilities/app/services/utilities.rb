@@ -0,0 +1,5 @@
+module Utilities
+  def self.call
+    Ui::Formatter
+  end
+end